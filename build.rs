@@ -0,0 +1,35 @@
+use std::{env, fs, path::Path};
+
+// Finds every src/solvers/dayNpM.rs and generates both the module declarations and the registry table for them,
+// so adding a day is just a matter of dropping in the file
+fn main() {
+    let solvers_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src").join("solvers");
+    println!("cargo:rerun-if-changed={}", solvers_dir.display());
+
+    let mut solvers = fs::read_dir(&solvers_dir)
+        .expect("src/solvers should exist")
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_stem()?.to_str()?.to_string();
+            if path.extension()? != "rs" {
+                return None;
+            }
+            let (day, part) = name.strip_prefix("day")?.split_once('p')?;
+            Some((day.parse::<u32>().ok()?, part.parse::<u32>().ok()?, name, path))
+        })
+        .collect::<Vec<_>>();
+    solvers.sort();
+
+    let mut generated = String::new();
+    for (_, _, name, path) in &solvers {
+        generated += &format!("#[path = {:?}]\nmod {};\n", path.display().to_string(), name);
+    }
+    generated += "\nconst REGISTERED: &[RegisteredSolver] = &[\n";
+    for (day, part, name, _) in &solvers {
+        generated += &format!("    RegisteredSolver {{ day: {}, part: {}, solver: {}::SOLVER }},\n", day, part, name);
+    }
+    generated += "];\n";
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("solvers.rs");
+    fs::write(out_path, generated).expect("Should be able to write the solver registry");
+}
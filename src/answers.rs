@@ -0,0 +1,60 @@
+use std::{collections::HashMap, fmt::Display, fs, path::Path};
+
+use crate::{error::{Error, Result}, solvers::{Answer, AnswerValue}};
+
+// Known-good answers, keyed the same way as the solvers ("5p1", "17p2" and so on).
+// The file is plain TOML, e.g.
+//
+//   "5p1" = 35
+//   "5p2" = 46
+pub struct RecordedAnswers {
+    answers: HashMap<String, AnswerValue>
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Mismatch(AnswerValue),
+    Unrecorded
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "ok"),
+            Verdict::Mismatch(expected) => write!(f, "MISMATCH (expected {})", expected),
+            Verdict::Unrecorded => write!(f, "unrecorded")
+        }
+    }
+}
+
+impl RecordedAnswers {
+    pub fn load(path: &Path) -> Result<RecordedAnswers> {
+        let invalid = |message: String| Error::Answers { path: path.to_path_buf(), message };
+
+        let contents = fs::read_to_string(path)
+            .map_err(|source| Error::Io { path: Some(path.to_path_buf()), source })?;
+        let table = contents.parse::<toml::Table>()
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let answers = table.into_iter().map(|(key, value)| {
+            let value = match value {
+                toml::Value::Integer(n) => AnswerValue::from(n),
+                toml::Value::String(s) => AnswerValue::from(s),
+                _ => return Err(invalid(format!("answer for {key} must be an integer or a string")))
+            };
+            Ok((key, value))
+        }).collect::<Result<_>>()?;
+
+        Ok(RecordedAnswers { answers })
+    }
+
+    pub fn check(&self, key: &str, answer: &Answer) -> Verdict {
+        match self.answers.get(key) {
+            None => Verdict::Unrecorded,
+            // Compared as text so that "5p1" = "35" and "5p1" = 35 are both accepted
+            Some(expected) if expected.to_string() == answer.value.to_string() => Verdict::Correct,
+            Some(expected) => Verdict::Mismatch(expected.clone())
+        }
+    }
+}
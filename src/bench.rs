@@ -0,0 +1,115 @@
+use std::{fs, path::{Path, PathBuf}, time::Duration};
+
+use serde::Serialize;
+
+use crate::{error::{Error, Result}, solvers::{all_solvers, default_input_path, find_solver, read_input, run_solver, Timing}};
+
+// All durations are kept in nanoseconds so the JSON output is easy to diff between runs
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub min_ns: f64,
+    pub median_ns: f64,
+    pub mean_ns: f64,
+    pub std_dev_ns: f64
+}
+
+impl Stats {
+    fn from_samples(samples: &[Duration]) -> Stats {
+        let mut nanos = samples.iter().map(|d| d.as_nanos() as f64).collect::<Vec<_>>();
+        nanos.sort_by(|a, b| a.total_cmp(b));
+
+        let n = nanos.len();
+        let median = if n % 2 == 0 {
+            (nanos[n / 2 - 1] + nanos[n / 2]) / 2.0
+        } else {
+            nanos[n / 2]
+        };
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+
+        // Sample standard deviation, a single run has none to speak of
+        let variance = if n > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min_ns: nanos[0],
+            median_ns: median,
+            mean_ns: mean,
+            std_dev_ns: variance.sqrt()
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub day: String,
+    pub runs: usize,
+    // Only present for solvers that mark where their parsing ends
+    pub parse: Option<Stats>,
+    pub solve: Option<Stats>,
+    pub total: Stats
+}
+
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.2?}", Duration::from_secs_f64(nanos / 1e9))
+}
+
+fn print_report(report: &BenchReport) {
+    println!("Benchmark of {} over {} runs", report.day, report.runs);
+    println!("{:<6} {:>12} {:>12} {:>12} {:>12}", "", "min", "median", "mean", "std dev");
+
+    let stages = [("parse", &report.parse), ("solve", &report.solve)];
+    let stages = stages.iter()
+        .filter_map(|(name, stats)| Some((*name, stats.as_ref()?)))
+        .chain(std::iter::once(("total", &report.total)));
+
+    for (name, stats) in stages {
+        println!("{:<6} {:>12} {:>12} {:>12} {:>12}",
+            name,
+            format_nanos(stats.min_ns),
+            format_nanos(stats.median_ns),
+            format_nanos(stats.mean_ns),
+            format_nanos(stats.std_dev_ns)
+        );
+    }
+}
+
+// Runs a single solver `runs` times against an input that's read into memory up front, so file IO doesn't skew timings.
+// One extra warm-up run is done first and not counted.
+pub fn bench_day(day: String, input: Option<PathBuf>, runs: usize, json_output: Option<&Path>) -> Result<()> {
+    let solvers = all_solvers();
+    let solver = find_solver(&solvers, &day)?;
+
+    let path = input.unwrap_or_else(|| default_input_path(&day));
+    let data = read_input(&path)?;
+
+    let (answer, _) = run_solver(solver, &mut data.as_slice())?;
+    println!("{}", answer);
+
+    let timings = (0..runs)
+        .map(|_| run_solver(solver, &mut data.as_slice()).map(|(_, timing)| timing))
+        .collect::<Result<Vec<Timing>>>()?;
+
+    let parse = timings.iter().map(|t| t.parse).collect::<Option<Vec<_>>>();
+    let solve = timings.iter().map(|t| t.solve()).collect::<Option<Vec<_>>>();
+    let total = timings.iter().map(|t| t.total).collect::<Vec<_>>();
+
+    let report = BenchReport {
+        day,
+        runs,
+        parse: parse.as_deref().map(Stats::from_samples),
+        solve: solve.as_deref().map(Stats::from_samples),
+        total: Stats::from_samples(&total)
+    };
+
+    print_report(&report);
+
+    if let Some(json_path) = json_output {
+        let json = serde_json::to_string_pretty(&report).expect("Benchmark report should serialize");
+        fs::write(json_path, json).map_err(|source| Error::Io { path: Some(json_path.to_path_buf()), source })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::{fmt::Display, io, num::ParseIntError, path::PathBuf};
+
+use crate::helpers::vec2d::{CellError, GridError};
+
+#[derive(Debug)]
+pub enum Error {
+    UnknownDay(String),
+    // A path of None means we were reading from stdin
+    Io { path: Option<PathBuf>, source: io::Error },
+    // Line and column are 1-based, as they'd show up in an editor
+    Parse { line: Option<usize>, column: Option<usize>, message: String },
+    // The input parsed fine, but doesn't have an answer (e.g. a pipe map with no loop)
+    NoSolution(String),
+    Answers { path: PathBuf, message: String }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    // Parse errors take 0-based indices, the way they come out of enumerate()
+    pub fn parse(message: impl Into<String>) -> Error {
+        Error::Parse { line: None, column: None, message: message.into() }
+    }
+    pub fn parse_at_line(line: usize, message: impl Into<String>) -> Error {
+        Error::Parse { line: Some(line + 1), column: None, message: message.into() }
+    }
+    pub fn parse_at_column(column: usize, message: impl Into<String>) -> Error {
+        Error::Parse { line: None, column: Some(column + 1), message: message.into() }
+    }
+    pub fn parse_at(line: usize, column: usize, message: impl Into<String>) -> Error {
+        Error::Parse { line: Some(line + 1), column: Some(column + 1), message: message.into() }
+    }
+
+    // For parsers that only see part of the input, so the caller fills in where it was
+    pub fn on_line(self, line: usize) -> Error {
+        match self {
+            Error::Parse { line: None, column, message } => Error::Parse { line: Some(line + 1), column, message },
+            other => other
+        }
+    }
+    pub fn on_column(self, column: usize) -> Error {
+        match self {
+            Error::Parse { line, column: None, message } => Error::Parse { line, column: Some(column + 1), message },
+            other => other
+        }
+    }
+
+    // Loosely follows the BSD sysexits conventions
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::UnknownDay(_) => 64,
+            Error::Parse { .. } => 65,
+            Error::Io { source, .. } if source.kind() == io::ErrorKind::NotFound => 66,
+            Error::Io { .. } => 74,
+            Error::NoSolution(_) => 70,
+            Error::Answers { .. } => 78
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownDay(day) => write!(f, "not a valid day to solve: {}", day),
+            Error::Io { path: Some(path), source } => write!(f, "cannot read {}: {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "cannot read input: {}", source),
+            Error::Parse { line, column, message } => match (line, column) {
+                (Some(line), Some(column)) => write!(f, "parse error at line {}, column {}: {}", line, column, message),
+                (Some(line), None) => write!(f, "parse error at line {}: {}", line, message),
+                _ => write!(f, "parse error: {}", message)
+            },
+            Error::NoSolution(message) => write!(f, "no solution: {}", message),
+            Error::Answers { path, message } => write!(f, "invalid answers file {}: {}", path.display(), message)
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Self {
+        Error::parse(format!("invalid number: {}", e))
+    }
+}
+
+impl<E: Display> From<GridError<E>> for Error {
+    fn from(e: GridError<E>) -> Self {
+        match e {
+            GridError::Cell(CellError { row, col, error }) => Error::parse_at(row, col, error.to_string()),
+            GridError::Ragged { row, expected, found } =>
+                Error::parse_at_line(row, format!("line is {} characters long, expected {} like the first line", found, expected)),
+            GridError::Empty => Error::parse("grid is empty")
+        }
+    }
+}
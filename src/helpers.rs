@@ -1,5 +1,18 @@
 pub mod vec2d;
+pub mod vec2d_view;
+pub mod compressed_grid;
+pub mod cycle;
 pub mod direction;
+pub mod image;
+pub mod interval;
+pub mod monotonic;
+pub mod numtheory;
 pub mod offset;
 pub mod point;
-pub mod polygon;
\ No newline at end of file
+pub mod polygon;
+pub mod polynomial;
+pub mod quadratic;
+pub mod regions;
+pub mod render;
+pub mod search;
+pub mod sparse_grid;
\ No newline at end of file
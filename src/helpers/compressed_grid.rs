@@ -0,0 +1,178 @@
+use super::vec2d::Vec2d;
+
+// One axis of a compressed grid. Each compact index stands for a band of consecutive original coordinates,
+// and the band's length is its weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis {
+    // Where each band starts in original coordinates, plus where the last one ends
+    starts: Vec<i64>
+}
+
+impl CompressedAxis {
+    // Bands laid end to end from `origin`, e.g. the rows of a map where some of them count for more
+    pub fn from_weights(origin: i64, weights: impl IntoIterator<Item = i64>) -> CompressedAxis {
+        let mut starts = vec![origin];
+        for weight in weights {
+            assert!(weight > 0, "band weights must be positive");
+            starts.push(starts.last().unwrap() + weight);
+        }
+        CompressedAxis { starts }
+    }
+
+    // Every coordinate given gets a band of its own, and each run of coordinates between them is squashed into one band.
+    // For rectilinear shapes, pass both sides of every edge (e.g. x and x + 1) so each band is either all in or all out.
+    pub fn from_coordinates(coordinates: impl IntoIterator<Item = i64>) -> CompressedAxis {
+        let mut coordinates = coordinates.into_iter().collect::<Vec<_>>();
+        coordinates.sort_unstable();
+        coordinates.dedup();
+
+        let mut starts = Vec::new();
+        for (i, coordinate) in coordinates.iter().enumerate() {
+            starts.push(*coordinate);
+            if coordinates.get(i + 1).is_some_and(|next| *next > coordinate + 1) {
+                starts.push(coordinate + 1);
+            }
+        }
+        if let Some(last) = coordinates.last() {
+            starts.push(last + 1);
+        }
+        CompressedAxis { starts }
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len().saturating_sub(1)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The first original coordinate of a band
+    pub fn start(&self, index: usize) -> i64 {
+        self.starts[index]
+    }
+    pub fn weight(&self, index: usize) -> i64 {
+        self.starts[index + 1] - self.starts[index]
+    }
+
+    // The band an original coordinate falls into
+    pub fn index_of(&self, coordinate: i64) -> Option<usize> {
+        if self.is_empty() || coordinate < self.starts[0] || coordinate >= *self.starts.last().unwrap() {
+            return None;
+        }
+        Some(self.starts.partition_point(|start| *start <= coordinate) - 1)
+    }
+
+    // In original coordinates, between the starts of two bands
+    pub fn distance(&self, from: usize, to: usize) -> i64 {
+        (self.starts[to] - self.starts[from]).abs()
+    }
+}
+
+// A small Vec2d standing in for a much bigger grid, where each cell covers a whole block of the original one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedGrid<T> {
+    pub grid: Vec2d<T>,
+    pub rows: CompressedAxis,
+    pub cols: CompressedAxis
+}
+
+impl<T> CompressedGrid<T> {
+    pub fn new(grid: Vec2d<T>, rows: CompressedAxis, cols: CompressedAxis) -> CompressedGrid<T> {
+        assert_eq!(grid.height, rows.len(), "grid height should match the row bands");
+        assert_eq!(grid.width, cols.len(), "grid width should match the column bands");
+        CompressedGrid { grid, rows, cols }
+    }
+
+    // A grid of `default` covering every point given, to be filled in through position_of
+    pub fn from_points(points: impl IntoIterator<Item = (i64, i64)>, default: T) -> CompressedGrid<T> where T: Clone {
+        let (rows, cols): (Vec<_>, Vec<_>) = points.into_iter().unzip();
+        let rows = CompressedAxis::from_coordinates(rows);
+        let cols = CompressedAxis::from_coordinates(cols);
+        CompressedGrid { grid: Vec2d::new(cols.len(), rows.len(), default), rows, cols }
+    }
+
+    // Original coordinates to the compact cell covering them
+    pub fn position_of(&self, row: i64, col: i64) -> Option<(usize, usize)> {
+        Some((self.rows.index_of(row)?, self.cols.index_of(col)?))
+    }
+    // The top left corner of a compact cell's block, in original coordinates
+    pub fn original_position(&self, row: usize, col: usize) -> (i64, i64) {
+        (self.rows.start(row), self.cols.start(col))
+    }
+    pub fn cell_area(&self, row: usize, col: usize) -> i64 {
+        self.rows.weight(row) * self.cols.weight(col)
+    }
+
+    // Manhattan distance in original coordinates between the top left corners of two compact cells
+    pub fn distance(&self, from: (usize, usize), to: (usize, usize)) -> i64 {
+        self.rows.distance(from.0, to.0) + self.cols.distance(from.1, to.1)
+    }
+
+    // How much of the original grid is covered by cells matching the predicate
+    pub fn area(&self, predicate: impl Fn(&T) -> bool) -> i64 {
+        self.grid.enumerate()
+            .filter(|(_, _, value)| predicate(value))
+            .map(|(row, col, _)| self.cell_area(row, col))
+            .sum()
+    }
+
+    // Flood fill from seeds in original coordinates, giving the original area reached.
+    // Seeds outside the compressed grid are skipped.
+    pub fn flood_fill_area(&self, seeds: impl IntoIterator<Item = (i64, i64)>, passable: impl Fn(&T) -> bool) -> i64 {
+        let seeds = seeds.into_iter().filter_map(|(row, col)| self.position_of(row, col)).collect::<Vec<_>>();
+        let filled = self.grid.flood_fill(seeds, passable);
+        filled.enumerate()
+            .filter(|(_, _, filled)| **filled)
+            .map(|(row, col, _)| self.cell_area(row, col))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squashes_gaps_between_coordinates() {
+        let axis = CompressedAxis::from_coordinates([10, -5, 11, 1_000_000]);
+        assert_eq!(axis.len(), 6);
+        assert_eq!((0..axis.len()).map(|i| axis.weight(i)).collect::<Vec<_>>(), vec![1, 14, 1, 1, 999_988, 1]);
+        assert_eq!(axis.index_of(-5), Some(0));
+        assert_eq!(axis.index_of(500), Some(4));
+        assert_eq!(axis.index_of(1_000_001), None);
+        assert_eq!(axis.distance(0, 5), 1_000_005);
+    }
+
+    #[test]
+    fn weighted_distances() {
+        // The middle row and column each stand for 10
+        let grid = Vec2d::new(3, 3, ());
+        let compressed = CompressedGrid::new(
+            grid,
+            CompressedAxis::from_weights(0, [1, 10, 1]),
+            CompressedAxis::from_weights(0, [1, 10, 1])
+        );
+        assert_eq!(compressed.distance((0, 0), (2, 2)), 22);
+        assert_eq!(compressed.position_of(5, 11), Some((1, 2)));
+        assert_eq!(compressed.area(|_| true), 144);
+    }
+
+    #[test]
+    fn fills_large_rectangles() {
+        // A hollow square from (0, 0) to (1000, 1000), one cell thick
+        let corners = [(0, 0), (0, 1000), (1000, 0), (1000, 1000)];
+        let mut compressed = CompressedGrid::from_points(
+            corners.iter().flat_map(|(row, col)| [(*row, *col), (row + 1, col + 1)]),
+            false
+        );
+        for row in 0..compressed.grid.height {
+            for col in 0..compressed.grid.width {
+                let (r, c) = compressed.original_position(row, col);
+                let on_edge = (r == 0 || r == 1000) && (0..=1000).contains(&c) || (c == 0 || c == 1000) && (0..=1000).contains(&r);
+                compressed.grid.set(row, col, on_edge);
+            }
+        }
+        assert_eq!(compressed.area(|wall| *wall), 4000);
+        assert_eq!(compressed.flood_fill_area([(500, 500)], |wall| !*wall), 999 * 999);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{collections::{hash_map::DefaultHasher, HashMap}, hash::{Hash, Hasher}};
+
+// Where repeatedly stepping a state starts going round in circles:
+// the state after `start` steps is the first one to come back, and it does so every `length` steps.
+// All of these expect the states to cycle eventually, and will loop forever if they don't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize
+}
+
+impl Cycle {
+    // An iteration no further in than start + length that has the same state as iteration n
+    pub fn equivalent_iteration(&self, n: usize) -> usize {
+        if n < self.start {n} else {self.start + (n - self.start) % self.length}
+    }
+}
+
+fn stepped<S: Clone>(state: &S, step: &mut impl FnMut(&mut S), times: usize) -> S {
+    let mut state = state.clone();
+    for _ in 0..times {
+        step(&mut state);
+    }
+    state
+}
+
+// Brent's algorithm, which only keeps two states around at a time and steps fewer times than Floyd's
+pub fn brent<S: Clone + PartialEq>(initial: &S, mut step: impl FnMut(&mut S)) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = stepped(initial, &mut step, 1);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        step(&mut hare);
+        length += 1;
+    }
+
+    // With the hare a whole cycle ahead, they first meet where the cycle starts
+    let mut tortoise = initial.clone();
+    let mut hare = stepped(initial, &mut step, length);
+    let mut start = 0;
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+// Floyd's tortoise and hare
+pub fn floyd<S: Clone + PartialEq>(initial: &S, mut step: impl FnMut(&mut S)) -> Cycle {
+    let mut tortoise = stepped(initial, &mut step, 1);
+    let mut hare = stepped(initial, &mut step, 2);
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        step(&mut hare);
+    }
+
+    let mut tortoise = initial.clone();
+    let mut start = 0;
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        start += 1;
+    }
+
+    let mut hare = stepped(&tortoise, &mut step, 1);
+    let mut length = 1;
+    while tortoise != hare {
+        step(&mut hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+fn fingerprint<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Steps forward once per iteration and remembers every state it has seen in a hash map.
+// Uses more memory than Brent's or Floyd's, but never steps anything twice, which suits small states.
+pub fn find_cycle_hashed<S: Hash + Eq + Clone>(mut state: S, mut step: impl FnMut(&mut S)) -> Cycle {
+    let mut seen = HashMap::new();
+    for iteration in 0.. {
+        if let Some(start) = seen.insert(state.clone(), iteration) {
+            return Cycle { start, length: iteration - start };
+        }
+        step(&mut state);
+    }
+    unreachable!()
+}
+
+// The state after n steps, skipping over every full trip round the cycle.
+// Only keeps a 64 bit hash of each state, for states too big to keep every one of, e.g. whole grids.
+// A matching hash is checked by stepping a copy once round the cycle and comparing before skipping anything,
+// so a hash collision only costs a few extra steps rather than a wrong answer. Stops early if n comes before the cycle is found.
+pub fn nth_state<S: Hash + Clone + PartialEq>(mut state: S, mut step: impl FnMut(&mut S), n: usize) -> S {
+    let mut seen = HashMap::new();
+    for iteration in 0..n {
+        if let Some(start) = seen.insert(fingerprint(&state), iteration) {
+            let length = iteration - start;
+            if stepped(&state, &mut step, length) == state {
+                for _ in 0..(n - iteration) % length {
+                    step(&mut state);
+                }
+                return state;
+            }
+        }
+        step(&mut state);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, ..., 10, then back to 3
+    fn step(state: &mut u32) {
+        *state = if *state < 10 {*state + 1} else {3};
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let expected = Cycle { start: 3, length: 8 };
+        assert_eq!(brent(&0, step), expected);
+        assert_eq!(floyd(&0, step), expected);
+        assert_eq!(find_cycle_hashed(0, step), expected);
+        assert_eq!(brent(&3, step), Cycle { start: 0, length: 8 });
+    }
+
+    #[test]
+    fn jumps_to_state() {
+        let cycle = brent(&0, step);
+        assert_eq!(cycle.equivalent_iteration(2), 2);
+        assert_eq!(cycle.equivalent_iteration(1_000_000_000), 3 + (1_000_000_000 - 3) % 8);
+
+        let direct = |n: usize| stepped(&0, &mut step, n);
+        for n in [0, 5, 11, 12, 100, 1_000_003] {
+            assert_eq!(nth_state(0, step, n), direct(n));
+        }
+    }
+
+    // Hashes only the last digit, so every tenth state collides with the one before it
+    #[derive(Clone, PartialEq, Eq)]
+    struct Colliding(u32);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            (self.0 % 10).hash(state);
+        }
+    }
+
+    #[test]
+    fn survives_hash_collisions() {
+        let count_to_1000 = |state: &mut Colliding| state.0 = (state.0 + 1) % 1000;
+        assert_eq!(nth_state(Colliding(0), count_to_1000, 12_345).0, 345);
+        assert_eq!(nth_state(Colliding(0), count_to_1000, 995).0, 995);
+        // Storing whole states, find_cycle_hashed isn't fooled by the hashes either
+        assert_eq!(find_cycle_hashed(Colliding(0), count_to_1000), Cycle { start: 0, length: 1000 });
+    }
+}
@@ -0,0 +1,217 @@
+use std::{fs::{self, File}, io::{self, BufWriter, Write}, path::{Path, PathBuf}};
+
+use super::vec2d::Vec2d;
+
+pub type Rgb = [u8; 3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Ppm,
+    Png
+}
+
+impl Format {
+    fn from_extension(extension: Option<&str>) -> io::Result<Format> {
+        match extension {
+            Some("ppm") => Ok(Format::Ppm),
+            Some("png") => Ok(Format::Png),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unsupported image format {}", extension.unwrap_or("(none)"))))
+        }
+    }
+}
+
+// A plain RGB picture, usually made from a grid with Vec2d::to_image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<u8>
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        let start = (y * self.width + x) * 3;
+        [self.pixels[start], self.pixels[start + 1], self.pixels[start + 2]]
+    }
+
+    // Every pixel becomes a `scale` by `scale` block, since a pixel per cell is hard to see
+    pub fn scaled(&self, scale: usize) -> Image {
+        let width = self.width * scale;
+        let height = self.height * scale;
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| self.pixel(x / scale, y / scale))
+            .collect();
+        Image { width, height, pixels }
+    }
+
+    // Binary PPM (P6), which needs no encoder and most image viewers open
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels)
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    // Picks the format from the extension, .ppm or .png
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        // Checked before creating the file, so an unsupported name doesn't leave an empty file behind
+        let format = Format::from_extension(path.extension().and_then(|extension| extension.to_str()))?;
+        let writer = BufWriter::new(File::create(path)?);
+        match format {
+            Format::Ppm => self.write_ppm(writer),
+            Format::Png => self.write_png(writer)
+        }
+    }
+}
+
+fn gif_error(error: gif::EncodingError) -> io::Error {
+    match error {
+        gif::EncodingError::Io(error) => error,
+        error => io::Error::other(error)
+    }
+}
+
+impl<T> Vec2d<T> {
+    // One pixel per cell, coloured by `colour`
+    pub fn to_image(&self, colour: impl Fn(&T) -> Rgb) -> Image {
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels: self.iter().flat_map(colour).collect()
+        }
+    }
+}
+
+// Snapshots a grid as it changes during a simulation, to play it back afterwards
+// either as numbered images or as an animated GIF
+pub struct FrameRecorder<T> {
+    colour: Box<dyn Fn(&T) -> Rgb>,
+    scale: usize,
+    frames: Vec<Image>
+}
+
+impl<T> FrameRecorder<T> {
+    pub fn new(colour: impl Fn(&T) -> Rgb + 'static, scale: usize) -> FrameRecorder<T> {
+        FrameRecorder { colour: Box::new(colour), scale, frames: Vec::new() }
+    }
+
+    pub fn record(&mut self, grid: &Vec2d<T>) {
+        let image = grid.to_image(&self.colour);
+        self.frames.push(if self.scale > 1 {image.scaled(self.scale)} else {image});
+    }
+
+    pub fn frames(&self) -> &[Image] {
+        &self.frames
+    }
+
+    // Writes frame_0000.png, frame_0001.png and so on into `dir`, creating it if needed.
+    // `extension` picks the format as in Image::save.
+    pub fn write_sequence(&self, dir: impl AsRef<Path>, extension: &str) -> io::Result<Vec<PathBuf>> {
+        Format::from_extension(Some(extension))?;
+        fs::create_dir_all(&dir)?;
+        self.frames.iter().enumerate().map(|(i, frame)| {
+            let path = dir.as_ref().join(format!("frame_{:04}.{}", i, extension));
+            frame.save(&path)?;
+            Ok(path)
+        }).collect()
+    }
+
+    // An endlessly looping animation, with `delay` in hundredths of a second between frames
+    pub fn write_gif(&self, writer: impl Write, delay: u16) -> io::Result<()> {
+        let width = self.frames.iter().map(|frame| frame.width).max().unwrap_or(0);
+        let height = self.frames.iter().map(|frame| frame.height).max().unwrap_or(0);
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "frames are too large for a gif"));
+        }
+
+        let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &[]).map_err(gif_error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+        for image in &self.frames {
+            let mut frame = gif::Frame::from_rgb_speed(image.width as u16, image.height as u16, &image.pixels, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(gif_error)?;
+        }
+        Ok(())
+    }
+    pub fn save_gif(&self, path: impl AsRef<Path>, delay: u16) -> io::Result<()> {
+        self.write_gif(BufWriter::new(File::create(path)?), delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colour(wall: &bool) -> Rgb {
+        if *wall {[0, 0, 0]} else {[255, 255, 255]}
+    }
+
+    fn grid() -> Vec2d<bool> {
+        Vec2d::from_data(2, 2, vec![true, false, false, true])
+    }
+
+    #[test]
+    fn writes_ppm() {
+        let mut bytes = Vec::new();
+        grid().to_image(colour).write_ppm(&mut bytes).unwrap();
+        assert!(bytes.starts_with(b"P6\n2 2\n255\n"));
+        assert_eq!(bytes.len(), 11 + 2 * 2 * 3);
+    }
+
+    #[test]
+    fn round_trips_png() {
+        let image = grid().to_image(colour).scaled(3);
+        assert_eq!((image.width, image.height), (6, 6));
+        assert_eq!(image.pixel(4, 1), [255, 255, 255]);
+
+        let mut bytes = Vec::new();
+        image.write_png(&mut bytes).unwrap();
+        let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut decoded).unwrap();
+        assert_eq!(decoded, image.pixels);
+    }
+
+    #[test]
+    fn records_frames() {
+        let mut grid = grid();
+        let mut recorder = FrameRecorder::new(colour, 2);
+        for _ in 0..3 {
+            recorder.record(&grid);
+            grid.set(0, 0, !*grid.get(0, 0));
+        }
+        assert_eq!(recorder.frames().len(), 3);
+        assert_eq!(recorder.frames()[1].pixel(0, 0), [255, 255, 255]);
+
+        let mut bytes = Vec::new();
+        recorder.write_gif(&mut bytes, 10).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(bytes.as_slice()).unwrap();
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+    }
+
+    #[test]
+    fn rejects_unsupported_formats_without_writing() {
+        let dir = std::env::temp_dir().join(format!("advent-image-test-{}", std::process::id()));
+        let path = dir.join("out.jpg");
+        assert!(grid().to_image(colour).save(&path).is_err());
+        assert!(!path.exists());
+
+        let mut recorder = FrameRecorder::new(colour, 1);
+        recorder.record(&grid());
+        assert!(recorder.write_sequence(&dir, "jpg").is_err());
+        assert!(!dir.exists());
+    }
+}
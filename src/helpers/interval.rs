@@ -0,0 +1,302 @@
+use num::PrimInt;
+
+// The half open range [start, end), so touching intervals don't overlap and the length is just end - start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval { start, end }
+    }
+    pub fn from_length(start: T, length: T) -> Interval<T> {
+        Interval { start, end: start + length }
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {T::zero()} else {self.end - self.start}
+    }
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+    pub fn contains(&self, value: T) -> bool {
+        value >= self.start && value < self.end
+    }
+
+    // None if they don't overlap at all
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let overlap = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        if overlap.is_empty() {None} else {Some(overlap)}
+    }
+}
+
+// Any number of intervals, kept sorted with overlapping and touching ones merged together
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { intervals: Vec::new() }
+    }
+
+    pub fn from_intervals(intervals: impl IntoIterator<Item = Interval<T>>) -> IntervalSet<T> {
+        let mut sorted = intervals.into_iter().filter(|interval| !interval.is_empty()).collect::<Vec<_>>();
+        sorted.sort();
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval)
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+    // How many values are in the set
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::zero(), |total, interval| total + interval.len())
+    }
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|interval| interval.end - T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= value);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        *self = IntervalSet::from_intervals(self.intervals.iter().copied().chain([interval]));
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        IntervalSet::from_intervals(self.intervals.iter().chain(&other.intervals).copied())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            result.extend(a.intersection(&b));
+            // Whichever ends first can't overlap anything further along in the other set
+            if a.end < b.end {i += 1} else {j += 1}
+        }
+        IntervalSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for interval in &self.intervals {
+            while j < other.intervals.len() && other.intervals[j].end <= interval.start {
+                j += 1;
+            }
+            let mut cursor = interval.start;
+            for removed in other.intervals[j..].iter().take_while(|removed| removed.start < interval.end) {
+                if removed.start > cursor {
+                    result.push(Interval::new(cursor, removed.start));
+                }
+                cursor = cursor.max(removed.end);
+            }
+            if cursor < interval.end {
+                result.push(Interval::new(cursor, interval.end));
+            }
+        }
+        IntervalSet { intervals: result }
+    }
+}
+
+// A map from values to values that shifts whole intervals at a time, like the almanac maps of 2023 day 5.
+// Anything outside every segment maps to itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PiecewiseMap<T> {
+    // Source interval and where its start maps to, sorted and never overlapping
+    segments: Vec<(Interval<T>, T)>
+}
+
+impl<T: PrimInt> PiecewiseMap<T> {
+    // Maps everything to itself
+    pub fn identity() -> PiecewiseMap<T> {
+        PiecewiseMap { segments: Vec::new() }
+    }
+
+    // Each segment moves its source interval so that it starts at the given destination.
+    // None if any two source intervals overlap, since a value can't go two places at once.
+    pub fn from_segments(segments: impl IntoIterator<Item = (Interval<T>, T)>) -> Option<PiecewiseMap<T>> {
+        let mut segments = segments.into_iter().filter(|(source, _)| !source.is_empty()).collect::<Vec<_>>();
+        segments.sort_by_key(|(source, _)| source.start);
+        if segments.windows(2).any(|pair| pair[0].0.end > pair[1].0.start) {
+            return None;
+        }
+        Some(PiecewiseMap { segments })
+    }
+
+    pub fn segments(&self) -> &[(Interval<T>, T)] {
+        &self.segments
+    }
+
+    pub fn apply(&self, value: T) -> T {
+        let index = self.segments.partition_point(|(source, _)| source.end <= value);
+        match self.segments.get(index) {
+            Some((source, dest_start)) if source.contains(value) => *dest_start + (value - source.start),
+            _ => value
+        }
+    }
+
+    // Cuts an interval up along the segments, covering it completely, each piece with where its start maps to
+    fn pieces(&self, interval: Interval<T>) -> Vec<(Interval<T>, T)> {
+        let mut pieces = Vec::new();
+        if interval.is_empty() {
+            return pieces;
+        }
+        let mut cursor = interval.start;
+        let first = self.segments.partition_point(|(source, _)| source.end <= interval.start);
+        for (source, dest_start) in self.segments[first..].iter().take_while(|(source, _)| source.start < interval.end) {
+            if source.start > cursor {
+                pieces.push((Interval::new(cursor, source.start), cursor));
+            }
+            let overlap = source.intersection(&interval).unwrap();
+            pieces.push((overlap, *dest_start + (overlap.start - source.start)));
+            cursor = overlap.end;
+        }
+        if cursor < interval.end {
+            pieces.push((Interval::new(cursor, interval.end), cursor));
+        }
+        pieces
+    }
+
+    pub fn map_interval(&self, interval: Interval<T>) -> IntervalSet<T> {
+        IntervalSet::from_intervals(self.pieces(interval).into_iter().map(|(piece, dest_start)| Interval::from_length(dest_start, piece.len())))
+    }
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        IntervalSet::from_intervals(
+            set.intervals().iter()
+                .flat_map(|interval| self.pieces(*interval))
+                .map(|(piece, dest_start)| Interval::from_length(dest_start, piece.len()))
+        )
+    }
+
+    // A single map doing this one and then `next`
+    pub fn then(&self, next: &PiecewiseMap<T>) -> PiecewiseMap<T> {
+        // Only values that either map moves can end up moved, so those are the only ones worth splitting
+        let moved = IntervalSet::from_intervals(
+            self.segments.iter().map(|(source, _)| *source)
+                .chain(next.segments.iter().map(|(source, _)| *source))
+        );
+
+        let mut segments = Vec::new();
+        for interval in moved.intervals() {
+            for (piece, middle_start) in self.pieces(*interval) {
+                let middle = Interval::from_length(middle_start, piece.len());
+                for (part, dest_start) in next.pieces(middle) {
+                    let source_start = piece.start + (part.start - middle.start);
+                    if dest_start != source_start {
+                        segments.push((Interval::from_length(source_start, part.len()), dest_start));
+                    }
+                }
+            }
+        }
+        PiecewiseMap { segments }
+    }
+
+    // The map going back the other way. None unless every value has exactly one value mapping to it,
+    // i.e. the segments' destinations exactly cover their sources between them.
+    pub fn invert(&self) -> Option<PiecewiseMap<T>> {
+        let sources = IntervalSet::from_intervals(self.segments.iter().map(|(source, _)| *source));
+        let destinations = self.segments.iter()
+            .map(|(source, dest_start)| Interval::from_length(*dest_start, source.len()))
+            .collect::<Vec<_>>();
+        // Overlapping destinations would cover less than their lengths add up to
+        let total = destinations.iter().fold(T::zero(), |total, destination| total + destination.len());
+        if IntervalSet::from_intervals(destinations) != sources || total != sources.len() {
+            return None;
+        }
+
+        PiecewiseMap::from_segments(
+            self.segments.iter().map(|(source, dest_start)| (Interval::from_length(*dest_start, source.len()), source.start))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        IntervalSet::from_intervals(intervals.iter().map(|(start, end)| Interval::new(*start, *end)))
+    }
+
+    #[test]
+    fn normalises_sets() {
+        let merged = set(&[(5, 8), (0, 2), (2, 3), (7, 10), (20, 20)]);
+        assert_eq!(merged, set(&[(0, 3), (5, 10)]));
+        assert_eq!(merged.len(), 8);
+        assert!(merged.contains(9));
+        assert!(!merged.contains(3));
+        assert_eq!((merged.min(), merged.max()), (Some(0), Some(9)));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20), (30, 40)]));
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+    }
+
+    // The seed-to-soil map from 2023 day 5's example
+    fn seed_to_soil() -> PiecewiseMap<u64> {
+        PiecewiseMap::from_segments([(Interval::from_length(98, 2), 50), (Interval::from_length(50, 48), 52)]).unwrap()
+    }
+
+    #[test]
+    fn maps_values_and_sets() {
+        let map = seed_to_soil();
+        assert_eq!([0, 49, 50, 97, 98, 99, 100].map(|seed| map.apply(seed)), [0, 49, 52, 99, 50, 51, 100]);
+        let seeds = IntervalSet::from_intervals([Interval::from_length(79, 14), Interval::from_length(55, 13)]);
+        assert_eq!(map.map_set(&seeds), IntervalSet::from_intervals([Interval::new(57, 70), Interval::new(81, 95)]));
+        assert_eq!(map.map_interval(Interval::new(96, 101)), IntervalSet::from_intervals([Interval::new(50, 52), Interval::new(98, 101)]));
+
+        assert!(PiecewiseMap::from_segments([(Interval::new(0u64, 5), 10), (Interval::new(4, 6), 20)]).is_none());
+    }
+
+    #[test]
+    fn composes_maps() {
+        let first = seed_to_soil();
+        let second = PiecewiseMap::from_segments([(Interval::new(0, 60), 1000)]).unwrap();
+        let composed = first.then(&second);
+        for value in 0..120 {
+            assert_eq!(composed.apply(value), second.apply(first.apply(value)), "value {}", value);
+        }
+        assert_eq!(PiecewiseMap::identity().then(&first), first);
+    }
+
+    #[test]
+    fn inverts_maps() {
+        let map = seed_to_soil();
+        let inverse = map.invert().unwrap();
+        for value in 0..120 {
+            assert_eq!(inverse.apply(map.apply(value)), value);
+        }
+        // Two ranges landing in the same place can't be undone
+        assert!(PiecewiseMap::from_segments([(Interval::new(0u64, 5), 10)]).unwrap().invert().is_none());
+    }
+}
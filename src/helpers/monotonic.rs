@@ -0,0 +1,69 @@
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+// Rounds down, without overflowing even across the whole range of a signed type
+fn midpoint<T: PrimInt>(low: T, high: T) -> T {
+    (low & high) + ((low ^ high) >> 1)
+}
+
+// Binary search for the first value in the range where the predicate holds,
+// for a predicate that keeps holding from that point on. None if it never holds.
+// The range is inclusive so that searches can go all the way up to T::max_value().
+pub fn first_satisfying<T: PrimInt>(range: RangeInclusive<T>, mut predicate: impl FnMut(T) -> bool) -> Option<T> {
+    let (mut low, mut high) = range.into_inner();
+    if low > high {
+        return None;
+    }
+    while low < high {
+        let mid = midpoint(low, high);
+        if predicate(mid) {
+            high = mid;
+        } else {
+            low = mid + T::one();
+        }
+    }
+    predicate(low).then_some(low)
+}
+
+// The other way round, the last value where the predicate holds, for a predicate that never holds again once it stops
+pub fn last_satisfying<T: PrimInt>(range: RangeInclusive<T>, mut predicate: impl FnMut(T) -> bool) -> Option<T> {
+    let (mut low, mut high) = range.into_inner();
+    if low > high {
+        return None;
+    }
+    while low < high {
+        // Rounding up here, so that low always moves
+        let mid = midpoint(low, high) + ((low ^ high) & T::one());
+        if predicate(mid) {
+            low = mid;
+        } else {
+            high = mid - T::one();
+        }
+    }
+    predicate(low).then_some(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_boundaries() {
+        assert_eq!(first_satisfying(0u32..=100, |x| x * x > 50), Some(8));
+        assert_eq!(last_satisfying(0u32..=100, |x| x * x <= 50), Some(7));
+        assert_eq!(first_satisfying(0u32..=100, |_| false), None);
+        assert_eq!(last_satisfying(0u32..=100, |_| false), None);
+        let (low, high) = (5u8, 4);
+        assert_eq!(first_satisfying(low..=high, |_| true), None);
+    }
+
+    #[test]
+    fn searches_whole_type_range() {
+        assert_eq!(first_satisfying(u64::MIN..=u64::MAX, |x| x == u64::MAX), Some(u64::MAX));
+        assert_eq!(last_satisfying(u64::MIN..=u64::MAX, |_| true), Some(u64::MAX));
+        assert_eq!(first_satisfying(i64::MIN..=i64::MAX, |x| x >= -3), Some(-3));
+        assert_eq!(last_satisfying(i64::MIN..=i64::MAX, |x| x < i64::MIN + 2), Some(i64::MIN + 1));
+        assert_eq!(first_satisfying(i8::MIN..=i8::MAX, |x| x > 100), Some(101));
+    }
+}
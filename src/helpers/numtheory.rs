@@ -0,0 +1,198 @@
+// Products are worked out in i128, so anything that fits in an i64 modulus can't overflow on the way.
+// Results that don't fit back into an i64 come out as MergeError::Overflow rather than wrapping.
+
+// Finds g = gcd(a, b) along with x and y such that a*x + b*y = g. The gcd is never negative.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {(-old_r, -old_x, -old_y)} else {(old_r, old_x, old_y)}
+}
+
+// The x in [0, modulus) with a*x = 1 (mod modulus), if a and the modulus are coprime
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus))
+}
+
+// base^exponent mod modulus, by repeated squaring
+pub fn mod_pow(base: i64, mut exponent: u64, modulus: i64) -> i64 {
+    let modulus = modulus as i128;
+    let mut base = (base as i128).rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as i64
+}
+
+// x = residue (mod modulus), with the residue kept in [0, modulus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub residue: i64,
+    pub modulus: i64
+}
+
+impl Congruence {
+    pub fn new(residue: i64, modulus: i64) -> Congruence {
+        Congruence { residue: residue.rem_euclid(modulus), modulus }
+    }
+}
+
+// Why two congruences or progressions can't be combined into one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeError {
+    // Nothing satisfies both
+    Disjoint,
+    // Something does, but it doesn't fit in an i64
+    Overflow
+}
+
+// Chinese remainder theorem for two congruences whose moduli needn't be coprime.
+// All the solutions as one congruence modulo the lcm.
+pub fn crt_pair(a: Congruence, b: Congruence) -> Result<Congruence, MergeError> {
+    let (g, p, _) = extended_gcd(a.modulus, b.modulus);
+    let difference = b.residue - a.residue;
+    if difference % g != 0 {
+        return Err(MergeError::Disjoint);
+    }
+
+    // a.residue + a.modulus * k solves both when k = p * difference / g (mod b.modulus / g)
+    let step = (b.modulus / g) as i128;
+    let k = (p as i128 * (difference / g) as i128).rem_euclid(step);
+    let lcm = a.modulus as i128 * step;
+    let residue = (a.residue as i128 + a.modulus as i128 * k).rem_euclid(lcm);
+    let modulus = i64::try_from(lcm).map_err(|_| MergeError::Overflow)?;
+    Ok(Congruence { residue: residue as i64, modulus })
+}
+
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Result<Congruence, MergeError> {
+    congruences.into_iter().try_fold(Congruence::new(0, 1), crt_pair)
+}
+
+// Something that first happens at `first` and then every `period` after that.
+// A period of 0 means it only happens the once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Progression {
+    pub first: i64,
+    pub period: i64
+}
+
+impl Progression {
+    pub fn new(first: i64, period: i64) -> Progression {
+        Progression { first, period }
+    }
+    pub fn once(at: i64) -> Progression {
+        Progression { first: at, period: 0 }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        if self.period == 0 {
+            value == self.first
+        } else {
+            value >= self.first && (value - self.first) % self.period == 0
+        }
+    }
+}
+
+// The times both progressions happen together, e.g. when two things going round cycles of different lengths line up.
+// Unlike a plain lcm, this copes with offsets and cycles that don't start at 0.
+pub fn merge_progressions(a: Progression, b: Progression) -> Result<Progression, MergeError> {
+    if a.period == 0 {
+        return if b.contains(a.first) {Ok(a)} else {Err(MergeError::Disjoint)};
+    }
+    if b.period == 0 {
+        return if a.contains(b.first) {Ok(b)} else {Err(MergeError::Disjoint)};
+    }
+
+    let together = crt_pair(Congruence::new(a.first, a.period), Congruence::new(b.first, b.period))?;
+    // Neither has started before its first hit, so the first time they line up can't be before both have
+    let earliest = a.first.max(b.first);
+    let (residue, modulus) = (together.residue as i128, together.modulus as i128);
+    let behind = (earliest as i128 - residue).max(0);
+    let laps = (behind + modulus - 1) / modulus;
+    let first = i64::try_from(residue + laps * modulus).map_err(|_| MergeError::Overflow)?;
+    Ok(Progression::new(first, together.modulus))
+}
+
+// Ok(None) if there weren't any progressions to merge
+pub fn merge_all_progressions(progressions: impl IntoIterator<Item = Progression>) -> Result<Option<Progression>, MergeError> {
+    let mut progressions = progressions.into_iter();
+    let Some(first) = progressions.next() else {
+        return Ok(None);
+    };
+    progressions.try_fold(first, merge_progressions).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modular_arithmetic() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(extended_gcd(-4, 6).0, 2);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 12), None);
+
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+    }
+
+    #[test]
+    fn chinese_remainder_theorem() {
+        let solution = crt([Congruence::new(2, 3), Congruence::new(3, 5), Congruence::new(2, 7)]).unwrap();
+        assert_eq!(solution, Congruence::new(23, 105));
+
+        // Moduli sharing a factor only agree if the residues do on that factor
+        assert_eq!(crt_pair(Congruence::new(3, 4), Congruence::new(5, 6)), Ok(Congruence::new(11, 12)));
+        assert_eq!(crt_pair(Congruence::new(0, 4), Congruence::new(1, 6)), Err(MergeError::Disjoint));
+
+        // Large enough that the products overflow i64
+        let big = crt_pair(Congruence::new(1, 1_000_000_007), Congruence::new(2, 998_244_353)).unwrap();
+        assert_eq!(big.residue % 1_000_000_007, 1);
+        assert_eq!(big.residue % 998_244_353, 2);
+
+        // Consecutive numbers are coprime, and these two have an lcm past i64::MAX
+        assert_eq!(crt_pair(Congruence::new(1, 10_000_000_000), Congruence::new(0, 10_000_000_001)), Err(MergeError::Overflow));
+    }
+
+    #[test]
+    fn merges_progressions() {
+        // 5, 11, 17, 23, ... and 7, 17, 27, ... first meet at 17
+        assert_eq!(merge_progressions(Progression::new(5, 6), Progression::new(7, 10)), Ok(Progression::new(17, 30)));
+        // The congruences agree at 2, but the second one hasn't started by then
+        assert_eq!(merge_progressions(Progression::new(2, 4), Progression::new(14, 6)), Ok(Progression::new(14, 12)));
+        assert_eq!(merge_progressions(Progression::new(0, 2), Progression::new(1, 4)), Err(MergeError::Disjoint));
+
+        assert_eq!(merge_progressions(Progression::once(20), Progression::new(2, 6)), Ok(Progression::once(20)));
+        assert_eq!(merge_progressions(Progression::new(2, 6), Progression::once(21)), Err(MergeError::Disjoint));
+
+        let all = [Progression::new(3, 3), Progression::new(4, 4), Progression::new(5, 5)];
+        assert_eq!(merge_all_progressions(all), Ok(Some(Progression::new(60, 60))));
+        assert_eq!(merge_all_progressions([]), Ok(None));
+
+        // Meeting right at the top of the range is fine, but the next meeting after it doesn't fit
+        assert_eq!(merge_progressions(Progression::new(i64::MAX - 1, 2), Progression::new(0, 3)), Ok(Progression::new(i64::MAX - 1, 6)));
+        assert_eq!(merge_progressions(Progression::new(i64::MAX - 1, 3), Progression::new(1, 2)), Err(MergeError::Overflow));
+        assert_eq!(merge_progressions(Progression::new(0, 10_000_000_000), Progression::new(1, 10_000_000_001)), Err(MergeError::Overflow));
+    }
+}
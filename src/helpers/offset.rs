@@ -79,6 +79,24 @@ impl DivAssign<i64> for Offset {
 }
 
 impl Offset {
+    // Stencils for Vec2d::neighbours. Von Neumann is the 4 orthogonal neighbours, Moore adds the diagonals.
+    pub const VON_NEUMANN: [Offset; 4] = [
+        Offset { rows: -1, cols: 0 },
+        Offset { rows: 0, cols: 1 },
+        Offset { rows: 1, cols: 0 },
+        Offset { rows: 0, cols: -1 }
+    ];
+    pub const MOORE: [Offset; 8] = [
+        Offset { rows: -1, cols: -1 },
+        Offset { rows: -1, cols: 0 },
+        Offset { rows: -1, cols: 1 },
+        Offset { rows: 0, cols: 1 },
+        Offset { rows: 1, cols: 1 },
+        Offset { rows: 1, cols: 0 },
+        Offset { rows: 1, cols: -1 },
+        Offset { rows: 0, cols: -1 }
+    ];
+
     pub fn new(rows: i64, cols: i64) -> Offset {
         Offset { rows, cols }
     }
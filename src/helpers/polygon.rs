@@ -1,16 +1,210 @@
-use super::point::Point;
+use itertools::Itertools;
+use num::integer::gcd;
 
+use super::{direction::Directions, offset::Offset, point::Point};
+
+// Where a point lies relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside
+}
+
+// A simple polygon on the integer lattice, given by its corners in order.
+// The last corner connects back to the first, so there's no need to repeat it.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Polygon {
-    points: Vec<Point>,
+    points: Vec<Point<i64>>,
 }
 
 impl Polygon {
-    fn new(points: Vec<Point>) -> Self {
+    pub fn new(points: Vec<Point<i64>>) -> Self {
         Self { points }
     }
 
-    fn iter_segments(&self) -> impl Iterator<Item = (&Point, &Point)> {
-        self.points.iter().zip(self.points.iter().skip(1).chain(std::iter::once(&self.points[0])))
+    // Walks a path of steps from `start`, e.g. a dig plan or a pipe loop.
+    // Points use screen orientation like Offset does, so north is towards negative y.
+    // Each step has to be exactly one of N, E, S or W, and None if any is empty or combined.
+    pub fn from_steps(start: Point<i64>, steps: impl IntoIterator<Item = (Directions, i64)>) -> Option<Self> {
+        const SINGLE: [Directions; 4] = [Directions::N, Directions::E, Directions::S, Directions::W];
+        let mut points = vec![start];
+        let mut current = start;
+        for (direction, length) in steps {
+            if !SINGLE.contains(&direction) {
+                return None;
+            }
+            let offset = Offset::from_direction(direction) * length;
+            current += Point::new(offset.cols, offset.rows);
+            points.push(current);
+        }
+        // A closed path ends where it started, which is already the first corner
+        if points.len() > 1 && points.last() == points.first() {
+            points.pop();
+        }
+        Some(Self { points })
+    }
+
+    pub fn points(&self) -> &[Point<i64>] {
+        &self.points
+    }
+
+    pub fn iter_segments(&self) -> impl Iterator<Item = (&Point<i64>, &Point<i64>)> {
+        self.points.iter().zip(self.points.iter().skip(1).chain(self.points.first()))
+    }
+
+    // Twice the shoelace area, which keeps it an integer for any lattice polygon
+    pub fn doubled_area(&self) -> i64 {
+        self.iter_segments()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<i64>()
+            .abs()
+    }
+    pub fn area(&self) -> f64 {
+        self.doubled_area() as f64 / 2.0
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.iter_segments()
+            .map(|(a, b)| (((b.x - a.x).pow(2) + (b.y - a.y).pow(2)) as f64).sqrt())
+            .sum()
+    }
+
+    // Lattice points lying on the edges, corners included.
+    // For axis aligned polygons this is the same as the perimeter.
+    pub fn boundary_points(&self) -> i64 {
+        if self.points.is_empty() {
+            return 0;
+        }
+        // Flat polygons double back over their own edges, so count what the line covers from end to end instead
+        if self.is_collinear() {
+            let (first, last) = self.points.iter().minmax_by_key(|point| (point.x, point.y)).into_option().unwrap();
+            return gcd(last.x - first.x, last.y - first.y) + 1;
+        }
+        self.iter_segments()
+            .map(|(a, b)| gcd(b.x - a.x, b.y - a.y))
+            .sum()
+    }
+
+    // Pick's theorem, A = I + B/2 - 1, solved for I.
+    // Flat polygons (including ones with fewer than 3 corners) can't enclose anything, and the theorem doesn't hold for them.
+    pub fn interior_points(&self) -> i64 {
+        if self.is_collinear() {
+            return 0;
+        }
+        (self.doubled_area() - self.boundary_points() + 2) / 2
+    }
+
+    // Everything the polygon covers, e.g. the number of cubes dug out by a dig plan
+    pub fn enclosed_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    // Whether every corner lies on one line, which is always the case with fewer than 3 of them
+    pub fn is_collinear(&self) -> bool {
+        let Some(first) = self.points.first() else {
+            return true;
+        };
+        let Some(second) = self.points.iter().find(|point| *point != first) else {
+            return true;
+        };
+        self.points.iter().all(|point| (second.x - first.x) * (point.y - first.y) == (second.y - first.y) * (point.x - first.x))
+    }
+
+    pub fn contains(&self, point: Point<i64>) -> Containment {
+        let mut inside = false;
+        for (a, b) in self.iter_segments() {
+            if is_on_segment(point, *a, *b) {
+                return Containment::Boundary;
+            }
+            // Casting a ray towards positive x, and counting the edges it crosses
+            if (a.y > point.y) != (b.y > point.y) {
+                let lhs = (point.x - a.x) * (b.y - a.y);
+                let rhs = (point.y - a.y) * (b.x - a.x);
+                let crosses = if b.y > a.y {lhs < rhs} else {lhs > rhs};
+                inside ^= crosses;
+            }
+        }
+        if inside {Containment::Inside} else {Containment::Outside}
     }
 }
 
+fn is_on_segment(point: Point<i64>, a: Point<i64>, b: Point<i64>) -> bool {
+    let cross = (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x);
+    cross == 0
+        && point.x >= a.x.min(b.x) && point.x <= a.x.max(b.x)
+        && point.y >= a.y.min(b.y) && point.y <= a.y.max(b.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: i64) -> Polygon {
+        Polygon::new(vec![Point::new(0, 0), Point::new(size, 0), Point::new(size, size), Point::new(0, size)])
+    }
+
+    #[test]
+    fn measures_square() {
+        let square = square(4);
+        assert_eq!(square.area(), 16.0);
+        assert_eq!(square.perimeter(), 16.0);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+        assert_eq!(square.enclosed_points(), 25);
+    }
+
+    #[test]
+    fn measures_triangle() {
+        let triangle = Polygon::new(vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 3)]);
+        assert_eq!(triangle.doubled_area(), 12);
+        assert_eq!(triangle.perimeter(), 12.0);
+        assert_eq!(triangle.boundary_points(), 8);
+        assert_eq!(triangle.interior_points(), 3);
+    }
+
+    #[test]
+    fn classifies_points() {
+        let square = square(4);
+        assert_eq!(square.contains(Point::new(2, 2)), Containment::Inside);
+        assert_eq!(square.contains(Point::new(4, 1)), Containment::Boundary);
+        assert_eq!(square.contains(Point::new(0, 0)), Containment::Boundary);
+        assert_eq!(square.contains(Point::new(5, 2)), Containment::Outside);
+        assert_eq!(square.contains(Point::new(-1, 4)), Containment::Outside);
+    }
+
+    #[test]
+    fn builds_from_steps() {
+        // The dig plan from 2023 day 18's example
+        let steps = [
+            (Directions::E, 6), (Directions::S, 5), (Directions::W, 2), (Directions::S, 2),
+            (Directions::E, 2), (Directions::S, 2), (Directions::W, 5), (Directions::N, 2),
+            (Directions::W, 1), (Directions::N, 2), (Directions::E, 2), (Directions::N, 3),
+            (Directions::W, 2), (Directions::N, 2)
+        ];
+        let plan = Polygon::from_steps(Point::new(0, 0), steps).unwrap();
+        assert_eq!(plan.points().len(), 14);
+        assert_eq!(plan.enclosed_points(), 62);
+
+        assert!(Polygon::from_steps(Point::new(0, 0), [(Directions::N | Directions::E, 1)]).is_none());
+        assert!(Polygon::from_steps(Point::new(0, 0), [(Directions::empty(), 1)]).is_none());
+    }
+
+    #[test]
+    fn degenerate_polygons() {
+        let empty = Polygon::new(vec![]);
+        assert_eq!((empty.boundary_points(), empty.interior_points()), (0, 0));
+        let point = Polygon::new(vec![Point::new(3, 3)]);
+        assert_eq!((point.boundary_points(), point.interior_points()), (1, 0));
+        let segment = Polygon::new(vec![Point::new(0, 0), Point::new(4, 2)]);
+        assert_eq!((segment.boundary_points(), segment.interior_points()), (3, 0));
+        assert_eq!(segment.contains(Point::new(2, 1)), Containment::Boundary);
+
+        let flat = Polygon::new(vec![Point::new(0, 0), Point::new(2, 0), Point::new(4, 0)]);
+        assert!(flat.is_collinear());
+        assert_eq!((flat.boundary_points(), flat.interior_points()), (5, 0));
+        let there_and_back = Polygon::from_steps(Point::new(0, 0), [(Directions::E, 2), (Directions::E, 2), (Directions::W, 4)]).unwrap();
+        assert_eq!((there_and_back.boundary_points(), there_and_back.interior_points()), (5, 0));
+        assert!(!square(1).is_collinear());
+    }
+}
@@ -0,0 +1,129 @@
+use num::{BigInt, BigRational, One, Zero};
+
+fn rational(value: i64) -> BigRational {
+    BigRational::from_integer(BigInt::from(value))
+}
+
+// The polynomial through a run of evenly spaced samples, taken at x = 0, 1, 2, ...
+// It's kept as the first entry of each row of the difference table (Newton's forward differences),
+// in exact arithmetic, so it can be evaluated anywhere without rounding or overflow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewtonPolynomial {
+    differences: Vec<BigRational>
+}
+
+impl NewtonPolynomial {
+    pub fn fit(samples: &[i64]) -> NewtonPolynomial {
+        NewtonPolynomial::fit_rational(samples.iter().map(|sample| rational(*sample)).collect())
+    }
+
+    pub fn fit_rational(samples: Vec<BigRational>) -> NewtonPolynomial {
+        let mut differences = Vec::new();
+        let mut row = samples;
+        while !row.is_empty() {
+            differences.push(row[0].clone());
+            row = row.windows(2).map(|pair| &pair[1] - &pair[0]).collect();
+        }
+
+        // Trailing zero differences don't change anything, and dropping them leaves the true degree
+        while differences.last().is_some_and(|difference| difference.is_zero()) {
+            differences.pop();
+        }
+        NewtonPolynomial { differences }
+    }
+
+    // Like fit, but None if the samples don't come from a polynomial of at most `max_degree`.
+    // n samples always fit something of degree n - 1, so with fewer than max_degree + 2 of them
+    // there's no telling, and that's None as well.
+    pub fn fit_within_degree(samples: &[i64], max_degree: usize) -> Option<NewtonPolynomial> {
+        if samples.len() < max_degree + 2 {
+            return None;
+        }
+        let polynomial = NewtonPolynomial::fit(samples);
+        if polynomial.degree().is_some_and(|degree| degree > max_degree) {
+            return None;
+        }
+        Some(polynomial)
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.differences.len().checked_sub(1)
+    }
+
+    // Any integer x works, including negative ones and ones far past the samples
+    pub fn evaluate(&self, x: i64) -> BigRational {
+        let x = rational(x);
+        let mut total = BigRational::zero();
+        // x choose k, built up one factor at a time
+        let mut binomial = BigRational::one();
+        for (k, difference) in self.differences.iter().enumerate() {
+            total += difference * &binomial;
+            binomial = binomial * (&x - rational(k as i64)) / rational(k as i64 + 1);
+        }
+        total
+    }
+}
+
+// The value at x of the lowest degree polynomial through all the points, which can be spaced however.
+// Panics if two points share an x.
+pub fn lagrange(points: &[(i64, i64)], x: i64) -> BigRational {
+    let x = rational(x);
+    points.iter().enumerate().map(|(i, (xi, yi))| {
+        let basis = points.iter().enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(BigRational::one(), |basis, (_, (xj, _))| {
+                assert!(xi != xj, "lagrange interpolation needs distinct x values");
+                basis * (&x - rational(*xj)) / rational(xi - xj)
+            });
+        basis * rational(*yi)
+    }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use num::ToPrimitive;
+
+    use super::*;
+
+    fn integer(value: BigRational) -> i64 {
+        assert!(value.is_integer());
+        value.to_integer().to_i64().unwrap()
+    }
+
+    #[test]
+    fn extrapolates_both_ways() {
+        // n^2 + 1
+        let polynomial = NewtonPolynomial::fit(&[1, 2, 5, 10, 17]);
+        assert_eq!(polynomial.degree(), Some(2));
+        assert_eq!(integer(polynomial.evaluate(5)), 26);
+        assert_eq!(integer(polynomial.evaluate(-3)), 10);
+        assert_eq!(integer(polynomial.evaluate(1_000_000_000)), 1_000_000_000_000_000_001);
+        assert_eq!(NewtonPolynomial::fit(&[0, 0, 0]).degree(), None);
+    }
+
+    #[test]
+    fn detects_non_polynomials() {
+        let powers_of_two = [1, 2, 4, 8, 16, 32];
+        assert!(NewtonPolynomial::fit_within_degree(&powers_of_two, 3).is_none());
+        assert_eq!(NewtonPolynomial::fit(&powers_of_two).degree(), Some(5));
+        assert!(NewtonPolynomial::fit_within_degree(&[3, 5, 7, 9], 1).is_some());
+    }
+
+    #[test]
+    fn needs_enough_samples_to_check_degree() {
+        // Any three points fit a quadratic, so they can't confirm one
+        assert!(NewtonPolynomial::fit_within_degree(&[1, 2, 4], 2).is_none());
+        assert!(NewtonPolynomial::fit_within_degree(&[1, 2, 4, 7], 2).is_some());
+        assert!(NewtonPolynomial::fit_within_degree(&[], 0).is_none());
+    }
+
+    #[test]
+    fn interpolates_unevenly_spaced_points() {
+        // x^2 - x / 2, which isn't an integer everywhere
+        let points = [(0, 0), (2, 3), (4, 14)];
+        assert_eq!(integer(lagrange(&points, 6)), 33);
+        assert_eq!(lagrange(&points, 1), BigRational::new(BigInt::from(1), BigInt::from(2)));
+        assert_eq!(integer(lagrange(&points, -2)), 5);
+    }
+}
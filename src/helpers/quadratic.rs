@@ -0,0 +1,94 @@
+use std::ops::RangeInclusive;
+
+// Coefficients have to be smaller than this in magnitude, so the discriminant fits in an i128
+pub const COEFFICIENT_LIMIT: i64 = 1 << 62;
+
+fn evaluate(a: i128, b: i128, c: i128, x: i128) -> i128 {
+    (a * x + b) * x + c
+}
+
+// The integers x with a*x^2 + b*x + c > 0, for a parabola that opens downwards (a < 0), so they form a single finite range.
+// None if there aren't any. Works entirely in integers, using the integer square root of the discriminant to land
+// within one of each end and then checking exactly, so there's no floating point to get wrong.
+// Panics if any coefficient is outside COEFFICIENT_LIMIT.
+pub fn positive_range(a: i64, b: i64, c: i64) -> Option<RangeInclusive<i64>> {
+    assert!(a < 0, "only downward opening parabolas have a finite range of positive values");
+    assert!(
+        [a, b, c].iter().all(|coefficient| coefficient.unsigned_abs() < COEFFICIENT_LIMIT as u64),
+        "coefficients have to be smaller than 2^62 in magnitude"
+    );
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    let f = |x: i128| evaluate(a, b, c, x);
+
+    let discriminant = b * b - 4 * a * c;
+    if discriminant < 0 {
+        return None;
+    }
+    let root = discriminant.isqrt();
+
+    // The roots are (b -+ sqrt(discriminant)) / -2a, and rounding the square root down moves each of them
+    // by less than a half towards the other, so the first and last positive values are at most one or two away
+    let denominator = -2 * a;
+    let mut low = (b - root).div_euclid(denominator);
+    if f(low) <= 0 {
+        low += 1;
+    }
+    let mut high = (b + root).div_euclid(denominator) + 1;
+    for _ in 0..2 {
+        if f(high) <= 0 {
+            high -= 1;
+        }
+    }
+
+    if low > high || f(low) <= 0 {
+        return None;
+    }
+    Some(low as i64..=high as i64)
+}
+
+pub fn count_positive(a: i64, b: i64, c: i64) -> u64 {
+    positive_range(a, b, c).map_or(0, |range| (range.end() - range.start()) as u64 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(a: i64, b: i64, c: i64) -> u64 {
+        (-100..=100).filter(|x| a * x * x + b * x + c > 0).count() as u64
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for a in -4..0 {
+            for b in -12..=12 {
+                for c in -12..=12 {
+                    assert_eq!(count_positive(a, b, c), brute_force(a, b, c), "{}x^2 + {}x + {}", a, b, c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handles_touching_and_missing_roots() {
+        // -(x - 3)^2 is never above 0, only touching it at 3
+        assert_eq!(positive_range(-1, 6, -9), None);
+        assert_eq!(positive_range(-1, 0, -1), None);
+        // Roots at exactly 2 and 5 don't count, only what's strictly between them
+        assert_eq!(positive_range(-1, 7, -10), Some(3..=4));
+    }
+
+    #[test]
+    fn exact_for_large_coefficients() {
+        // The part 2 race from 2023 day 6's example, then one big enough that f64 can't hold the discriminant exactly
+        assert_eq!(count_positive(-1, 71530, -940200), 71503);
+        let time: i64 = 3_000_000_000_000_001;
+        assert_eq!(positive_range(-1, time, 0), Some(1..=time - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "2^62")]
+    fn rejects_oversized_coefficients() {
+        positive_range(-1, COEFFICIENT_LIMIT, 0);
+    }
+}
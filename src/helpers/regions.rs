@@ -0,0 +1,162 @@
+use super::{offset::Offset, vec2d::Vec2d};
+
+// The result of labelling a grid's connected components.
+// Cells that aren't passable have no label, the rest are numbered from 0 in the order they were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    pub labels: Vec2d<Option<usize>>,
+    // Cell count for each label
+    pub sizes: Vec<usize>,
+    // Whether any cell with that label is on the outer edge of the grid, which is usually what tells outside from inside
+    pub touches_edge: Vec<bool>
+}
+
+impl Components {
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+    pub fn label(&self, row: usize, col: usize) -> Option<usize> {
+        *self.labels.get(row, col)
+    }
+}
+
+impl<T> Vec2d<T> {
+    // Every cell reachable from the seeds through passable, 4-connected cells.
+    // Seeds that aren't passable themselves are skipped.
+    pub fn flood_fill(&self, seeds: impl IntoIterator<Item=(usize, usize)>, passable: impl Fn(&T) -> bool) -> Vec2d<bool> {
+        let mut filled = Vec2d::new(self.width, self.height, false);
+        self.fill_into(&mut filled, seeds, &passable, |_, _| {});
+        filled
+    }
+
+    // Flood fill that shares its `filled` grid between calls, and reports each cell as it's filled
+    fn fill_into(&self, filled: &mut Vec2d<bool>, seeds: impl IntoIterator<Item=(usize, usize)>, passable: &impl Fn(&T) -> bool, mut visit: impl FnMut(usize, usize)) {
+        let mut stack = seeds.into_iter().collect::<Vec<_>>();
+
+        while let Some((row, col)) = stack.pop() {
+            if *filled.get(row, col) || !passable(self.get(row, col)) {
+                continue;
+            }
+            filled.set(row, col, true);
+            visit(row, col);
+            stack.extend(
+                self.neighbours(row, col, &Offset::VON_NEUMANN)
+                    .filter(|(r, c, _)| !*filled.get(*r, *c))
+                    .map(|(r, c, _)| (r, c))
+            );
+        }
+    }
+
+    // Labels the 4-connected regions of passable cells
+    pub fn connected_components(&self, passable: impl Fn(&T) -> bool) -> Components {
+        let mut components = Components {
+            labels: Vec2d::new(self.width, self.height, None),
+            sizes: Vec::new(),
+            touches_edge: Vec::new()
+        };
+
+        let mut filled = Vec2d::new(self.width, self.height, false);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if *filled.get(row, col) || !passable(self.get(row, col)) {
+                    continue;
+                }
+
+                let label = components.sizes.len();
+                let mut size = 0;
+                let mut touches_edge = false;
+                self.fill_into(&mut filled, [(row, col)], &passable, |r, c| {
+                    components.labels.set(r, c, Some(label));
+                    size += 1;
+                    touches_edge |= r == 0 || c == 0 || r == self.height - 1 || c == self.width - 1;
+                });
+                components.sizes.push(size);
+                components.touches_edge.push(touches_edge);
+            }
+        }
+
+        components
+    }
+
+    // Blows every cell up into a 2x2 block, as given by `expand` for the cell's position and value
+    pub fn upscaled_2x<U: Clone>(&self, expand: impl Fn(usize, usize, &T) -> [[U; 2]; 2]) -> Vec2d<U> {
+        let blocks = self.enumerate().map(|(row, col, value)| expand(row, col, value)).collect::<Vec<_>>();
+        let width = self.width * 2;
+        let data = (0..self.height * 2)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| blocks[(row / 2) * self.width + col / 2][row % 2][col % 2].clone())
+            .collect();
+        Vec2d::from_data(width, self.height * 2, data)
+    }
+
+    // Connected components at sub-cell resolution, for when regions can squeeze between cells.
+    // `expand` says which of the 2x2 sub-cells of a cell are passable: the top left one is the cell itself,
+    // the others are the gaps to the right, below, and diagonally.
+    // Labels and sizes come back at the original resolution, only counting the cells themselves.
+    pub fn connected_components_2x(&self, expand: impl Fn(usize, usize, &T) -> [[bool; 2]; 2]) -> Components {
+        let upscaled = self.upscaled_2x(expand).connected_components(|passable| *passable);
+
+        let labels = Vec2d::from_data(
+            self.width,
+            self.height,
+            (0..self.height)
+                .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+                .map(|(row, col)| upscaled.label(row * 2, col * 2))
+                .collect()
+        );
+
+        let mut sizes = vec![0; upscaled.count()];
+        for label in labels.iter().flatten() {
+            sizes[*label] += 1;
+        }
+
+        Components {
+            labels,
+            sizes,
+            touches_edge: upscaled.touches_edge
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(lines: &[&str]) -> Vec2d<char> {
+        Vec2d::from_strings(lines.iter().map(|line| line.to_string()), |c| c).unwrap()
+    }
+
+    #[test]
+    fn flood_fill_stops_at_walls() {
+        let grid = grid(&["..#..", "..#..", "###.."]);
+        let filled = grid.flood_fill([(0, 0)], |c| *c == '.');
+        assert_eq!(filled.iter().filter(|f| **f).count(), 4);
+        assert!(!*filled.get(0, 3));
+    }
+
+    #[test]
+    fn labels_components() {
+        let grid = grid(&["..#..", "..#..", "###.#", "#.#.."]);
+        let components = grid.connected_components(|c| *c == '.');
+        assert_eq!(components.sizes, vec![4, 7, 1]);
+        assert_eq!(components.label(3, 4), components.label(0, 4));
+        assert_eq!(components.label(2, 0), None);
+        assert_eq!(components.touches_edge, vec![true, true, true]);
+    }
+
+    #[test]
+    fn sub_cell_resolution_squeezes_between_walls() {
+        // The middle cell is walled in, but only diagonally, so at 2x it connects to the corners
+        let grid = grid(&[".#.", "#.#", ".#."]);
+        assert_eq!(grid.connected_components(|c| *c == '.').count(), 5);
+
+        // Walls block their own sub-cell, and the gaps to their right and below only if the neighbour there is a wall too
+        let is_wall = |row: usize, col: usize| grid.try_get(row as i64, col as i64) == Some(&'#');
+        let components = grid.connected_components_2x(|row, col, _| {
+            let wall = is_wall(row, col);
+            [[!wall, !(wall && is_wall(row, col + 1))], [!(wall && is_wall(row + 1, col)), true]]
+        });
+        assert_eq!(components.sizes, vec![5]);
+        assert_eq!(components.label(0, 1), None);
+    }
+}
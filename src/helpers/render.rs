@@ -0,0 +1,143 @@
+use std::{collections::HashSet, fmt};
+
+use super::vec2d::Vec2d;
+
+// The 8 standard ANSI terminal colours
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White
+}
+
+impl Colour {
+    fn ansi_code(self) -> u8 {
+        30 + self as u8
+    }
+}
+
+type ColourFn<'a, T> = Box<dyn Fn(&T) -> Option<Colour> + 'a>;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_REVERSE: &str = "\x1b[7m";
+
+// Draws a grid as text, one glyph per cell, the opposite of Vec2d::from_strings.
+// Built with Vec2d::render and then printed with {}, e.g. println!("{}", grid.render(|c| *c).highlight(path)).
+pub struct Render<'a, T> {
+    grid: &'a Vec2d<T>,
+    glyph: Box<dyn Fn(&T) -> char + 'a>,
+    colour: Option<ColourFn<'a, T>>,
+    ansi: bool,
+    highlighted: HashSet<(usize, usize)>,
+    highlight_glyph: char
+}
+
+impl<'a, T> Render<'a, T> {
+    // Colours each cell with ANSI escape codes, None leaves the cell in the terminal's default colour
+    pub fn colour(mut self, colour: impl Fn(&T) -> Option<Colour> + 'a) -> Self {
+        self.colour = Some(Box::new(colour));
+        self.ansi = true;
+        self
+    }
+
+    // Turns ANSI escape codes on or off. Highlights are drawn in reverse video when on,
+    // and as the highlight glyph when off, which is what you want when the output isn't a terminal.
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    // Marks positions to stand out, like a path or a loop. Positions outside the grid are ignored.
+    pub fn highlight(mut self, positions: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        self.highlighted.extend(positions);
+        self
+    }
+    pub fn highlight_glyph(mut self, glyph: char) -> Self {
+        self.highlight_glyph = glyph;
+        self
+    }
+}
+
+impl<T> fmt::Display for Render<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.grid.height {
+            for col in 0..self.grid.width {
+                let value = self.grid.get(row, col);
+                let is_highlighted = self.highlighted.contains(&(row, col));
+
+                if !self.ansi {
+                    let glyph = if is_highlighted {self.highlight_glyph} else {(self.glyph)(value)};
+                    write!(f, "{}", glyph)?;
+                    continue;
+                }
+
+                let colour = self.colour.as_ref().and_then(|colour| colour(value));
+                if let Some(colour) = colour {
+                    write!(f, "\x1b[{}m", colour.ansi_code())?;
+                }
+                if is_highlighted {
+                    write!(f, "{}", ANSI_REVERSE)?;
+                }
+                write!(f, "{}", (self.glyph)(value))?;
+                if colour.is_some() || is_highlighted {
+                    write!(f, "{}", ANSI_RESET)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Vec2d<T> {
+    pub fn render<'a>(&'a self, glyph: impl Fn(&T) -> char + 'a) -> Render<'a, T> {
+        Render {
+            grid: self,
+            glyph: Box::new(glyph),
+            colour: None,
+            ansi: false,
+            highlighted: HashSet::new(),
+            highlight_glyph: '*'
+        }
+    }
+}
+
+impl fmt::Display for Vec2d<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(|c| *c).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Vec2d<u32> {
+        Vec2d::from_data(3, 2, vec![1, 2, 3, 4, 5, 6])
+    }
+
+    #[test]
+    fn renders_glyphs() {
+        let grid = grid();
+        let glyph = |value: &u32| char::from_digit(*value, 10).unwrap();
+        assert_eq!(grid.render(glyph).to_string(), "123\n456\n");
+        assert_eq!(grid.render(glyph).highlight([(0, 1), (1, 2), (5, 5)]).to_string(), "1*3\n45*\n");
+
+        let chars = Vec2d::from_strings(["#.".to_string(), ".#".to_string()].into_iter(), |c| c).unwrap();
+        assert_eq!(chars.to_string(), "#.\n.#\n");
+    }
+
+    #[test]
+    fn renders_ansi() {
+        let grid = grid();
+        let render = grid.render(|_| 'x')
+            .colour(|value| if *value == 1 {Some(Colour::Red)} else {None})
+            .highlight([(0, 1)]);
+        assert_eq!(render.to_string(), "\x1b[31mx\x1b[0m\x1b[7mx\x1b[0mx\nxxx\n");
+    }
+}
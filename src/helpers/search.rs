@@ -0,0 +1,217 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, cmp::Reverse, hash::Hash, ops::Add};
+
+use num::Zero;
+use priority_queue::PriorityQueue;
+
+// What a search found. Costs are only recorded once they are final,
+// so an early exit on a goal can leave out states that were still queued.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    costs: HashMap<S, C>,
+    parents: HashMap<S, S>,
+    goal: Option<S>
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> SearchResult<S, C> {
+    fn new() -> SearchResult<S, C> {
+        SearchResult { costs: HashMap::new(), parents: HashMap::new(), goal: None }
+    }
+
+    // The first state that satisfied the goal predicate, if any did
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+    pub fn goal_cost(&self) -> Option<C> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+    pub fn visited(&self) -> impl Iterator<Item=(&S, &C)> {
+        self.costs.iter()
+    }
+
+    // Every state from the start the search came from up to and including `state`
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.costs.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+// Breadth first search, where every step costs 1.
+// Pass `|_| false` as the goal to explore everything reachable.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool
+) -> SearchResult<S, usize>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>
+{
+    let mut result = SearchResult::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !result.costs.contains_key(&start) {
+            result.costs.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+        let cost = result.costs[&state];
+        for next in successors(&state) {
+            if result.costs.contains_key(&next) {
+                continue;
+            }
+            result.costs.insert(next.clone(), cost + 1);
+            result.parents.insert(next.clone(), state.clone());
+            queue.push_back(next);
+        }
+    }
+
+    result
+}
+
+// Cheapest paths for non-negative step costs
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool
+) -> SearchResult<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: IntoIterator<Item = (S, C)>
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+// Dijkstra guided by a heuristic. The heuristic must never overestimate the remaining cost,
+// and must be consistent, otherwise the costs found are not guaranteed to be the cheapest.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool
+) -> SearchResult<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: IntoIterator<Item = (S, C)>
+{
+    let mut result = SearchResult::new();
+    // Best cost found so far for everything seen, settled or not
+    let mut tentative: HashMap<S, C> = HashMap::new();
+    let mut tentative_parents: HashMap<S, S> = HashMap::new();
+    let mut settled = HashSet::new();
+
+    // Reverse so that the cheapest estimate comes out first
+    let mut queue = PriorityQueue::new();
+    for start in starts {
+        let estimate = heuristic(&start);
+        tentative.insert(start.clone(), C::zero());
+        queue.push(start, Reverse(estimate));
+    }
+
+    while let Some((state, _)) = queue.pop() {
+        let cost = tentative[&state];
+        settled.insert(state.clone());
+        result.costs.insert(state.clone(), cost);
+        if let Some(parent) = tentative_parents.remove(&state) {
+            result.parents.insert(state.clone(), parent);
+        }
+
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            if settled.contains(&next) {
+                continue;
+            }
+            let next_cost = cost + step_cost;
+            if tentative.get(&next).is_some_and(|existing| *existing <= next_cost) {
+                continue;
+            }
+            let estimate = next_cost + heuristic(&next);
+            tentative.insert(next.clone(), next_cost);
+            tentative_parents.insert(next.clone(), state.clone());
+            queue.push(next, Reverse(estimate));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Positions on a line, where each step moves one or two places right
+    fn line_steps(pos: &i32) -> Vec<(i32, u32)> {
+        vec![(pos + 1, 3), (pos + 2, 5)]
+    }
+
+    #[test]
+    fn bfs_finds_shortest_step_count() {
+        let result = bfs([0], |pos| [pos + 1, pos + 2], |pos| *pos == 7);
+        assert_eq!(result.goal_cost(), Some(4));
+        assert_eq!(result.goal_path().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn bfs_explores_everything_without_goal() {
+        let result = bfs([0], |pos: &i32| if *pos < 5 {vec![pos + 1]} else {vec![]}, |_| false);
+        assert_eq!(result.goal(), None);
+        assert_eq!(result.visited().count(), 6);
+        assert_eq!(result.path_to(&5), Some(vec![0, 1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_steps() {
+        // A double step costs 5 against 6 for two single ones
+        let result = dijkstra([0], line_steps, |pos| *pos == 4);
+        assert_eq!(result.goal_cost(), Some(10));
+        assert_eq!(result.goal_path(), Some(vec![0, 2, 4]));
+    }
+
+    #[test]
+    fn dijkstra_from_multiple_sources() {
+        let result = dijkstra([0, 3], line_steps, |pos| *pos == 4);
+        assert_eq!(result.goal_cost(), Some(3));
+        assert_eq!(result.goal_path(), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let heuristic = |pos: &i32| (10 - *pos).max(0) as u32 * 2;
+        let guided = astar([0], line_steps, heuristic, |pos| *pos == 10);
+        let unguided = dijkstra([0], line_steps, |pos| *pos == 10);
+        assert_eq!(guided.goal_cost(), unguided.goal_cost());
+        assert!(guided.visited().count() <= unguided.visited().count());
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let result = dijkstra([0], |pos: &i32| if *pos < 3 {vec![(pos + 1, 1u32)]} else {vec![]}, |pos| *pos == 10);
+        assert_eq!(result.goal_cost(), None);
+        assert_eq!(result.cost(&3), Some(3));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use super::{direction::Directions, offset::Offset, vec2d::Vec2d};
+
+// The smallest rectangle holding every set cell, bounds inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min_row: i64,
+    pub min_col: i64,
+    pub max_row: i64,
+    pub max_col: i64
+}
+
+impl BoundingBox {
+    pub fn height(&self) -> usize {
+        (self.max_row - self.min_row + 1) as usize
+    }
+    pub fn width(&self) -> usize {
+        (self.max_col - self.min_col + 1) as usize
+    }
+    pub fn contains(&self, row: i64, col: i64) -> bool {
+        row >= self.min_row && row <= self.max_row && col >= self.min_col && col <= self.max_col
+    }
+
+    fn including(self, row: i64, col: i64) -> BoundingBox {
+        BoundingBox {
+            min_row: self.min_row.min(row),
+            min_col: self.min_col.min(col),
+            max_row: self.max_row.max(row),
+            max_col: self.max_col.max(col)
+        }
+    }
+}
+
+// A grid without edges, which only stores the cells that were set and reads everything else as `default`.
+// Positions are signed, so it can grow in any direction from wherever the puzzle starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(i64, i64), T>,
+    default: T
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> SparseGrid<T> {
+        SparseGrid { cells: HashMap::new(), default }
+    }
+
+    pub fn get(&self, row: i64, col: i64) -> &T {
+        self.cells.get(&(row, col)).unwrap_or(&self.default)
+    }
+    // Only cells that were set, for telling them apart from ones that just hold the default
+    pub fn try_get(&self, row: i64, col: i64) -> Option<&T> {
+        self.cells.get(&(row, col))
+    }
+    pub fn get_mut(&mut self, row: i64, col: i64) -> &mut T where T: Clone {
+        self.cells.entry((row, col)).or_insert_with(|| self.default.clone())
+    }
+    pub fn set(&mut self, row: i64, col: i64, value: T) {
+        self.cells.insert((row, col), value);
+    }
+    // Puts a cell back to the default, handing back what was there
+    pub fn remove(&mut self, row: i64, col: i64) -> Option<T> {
+        self.cells.remove(&(row, col))
+    }
+
+    pub fn contains(&self, row: i64, col: i64) -> bool {
+        self.cells.contains_key(&(row, col))
+    }
+    // How many cells are set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // The set cells, in no particular order
+    pub fn enumerate(&self) -> impl Iterator<Item=(i64, i64, &T)> {
+        self.cells.iter().map(|((row, col), value)| (*row, *col, value))
+    }
+
+    // Never fails, since there are no edges to fall off
+    pub fn offset_position(&self, row: i64, col: i64, offset: Offset) -> (i64, i64) {
+        (row + offset.rows, col + offset.cols)
+    }
+
+    // Same as Vec2d::neighbours, but there is always one per offset in the stencil
+    pub fn neighbours<'a>(&'a self, row: i64, col: i64, stencil: &'a [Offset]) -> impl Iterator<Item=(i64, i64, &'a T)> + 'a {
+        stencil.iter()
+            .map(move |offset| self.offset_position(row, col, *offset))
+            .map(move |(row, col)| (row, col, self.get(row, col)))
+    }
+    pub fn von_neumann_neighbours(&self, row: i64, col: i64) -> impl Iterator<Item=(i64, i64, &T)> {
+        self.neighbours(row, col, &Offset::VON_NEUMANN)
+    }
+    pub fn moore_neighbours(&self, row: i64, col: i64) -> impl Iterator<Item=(i64, i64, &T)> {
+        self.neighbours(row, col, &Offset::MOORE)
+    }
+
+    pub fn directional_neighbours(&self, row: i64, col: i64, directions: Directions) -> impl Iterator<Item=(i64, i64, Directions)> + '_ {
+        directions.iter().map(move |direction| {
+            let (row, col) = self.offset_position(row, col, Offset::from_direction(direction));
+            (row, col, direction)
+        })
+    }
+
+    // None while nothing is set
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut positions = self.cells.keys();
+        let (row, col) = positions.next()?;
+        let first = BoundingBox { min_row: *row, min_col: *col, max_row: *row, max_col: *col };
+        Some(positions.fold(first, |bounds, (row, col)| bounds.including(*row, *col)))
+    }
+
+    // Copies the bounding box out into a dense grid, along with the position its top left corner had here
+    pub fn to_vec2d(&self) -> Option<(Vec2d<T>, (i64, i64))> where T: Clone {
+        let bounds = self.bounding_box()?;
+        let data = (bounds.min_row..=bounds.max_row)
+            .flat_map(|row| (bounds.min_col..=bounds.max_col).map(move |col| (row, col)))
+            .map(|(row, col)| self.get(row, col).clone())
+            .collect();
+        Some((Vec2d::from_data(bounds.width(), bounds.height(), data), (bounds.min_row, bounds.min_col)))
+    }
+
+    // Places a dense grid with its top left corner at `origin`.
+    // Cells equal to the default are left unset, so they don't count towards the bounding box.
+    pub fn from_vec2d(grid: &Vec2d<T>, origin: (i64, i64), default: T) -> SparseGrid<T> where T: Clone + PartialEq {
+        let cells = grid.enumerate()
+            .filter(|(_, _, value)| **value != default)
+            .map(|(row, col, value)| ((origin.0 + row as i64, origin.1 + col as i64), value.clone()))
+            .collect();
+        SparseGrid { cells, default }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_in_every_direction() {
+        let mut grid = SparseGrid::new('.');
+        grid.set(-1_000_000, 3, '#');
+        grid.set(5, -2, '#');
+        assert_eq!(*grid.get(5, -2), '#');
+        assert_eq!(*grid.get(0, 0), '.');
+        assert_eq!(grid.try_get(0, 0), None);
+        assert_eq!(grid.bounding_box(), Some(BoundingBox { min_row: -1_000_000, min_col: -2, max_row: 5, max_col: 3 }));
+
+        *grid.get_mut(1, 1) = 'x';
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.remove(1, 1), Some('x'));
+        assert_eq!(SparseGrid::new(0).bounding_box(), None);
+    }
+
+    #[test]
+    fn neighbours_never_run_out() {
+        let mut grid = SparseGrid::new(0);
+        grid.set(-1, 0, 7);
+        let found = grid.von_neumann_neighbours(0, 0).collect::<Vec<_>>();
+        assert_eq!(found, vec![(-1, 0, &7), (0, 1, &0), (1, 0, &0), (0, -1, &0)]);
+        let found = grid.directional_neighbours(0, 0, Directions::N | Directions::W).collect::<Vec<_>>();
+        assert_eq!(found, vec![(-1, 0, Directions::N), (0, -1, Directions::W)]);
+    }
+
+    #[test]
+    fn converts_to_and_from_vec2d() {
+        let dense = Vec2d::from_data(3, 2, vec![0, 1, 0, 2, 0, 0]);
+        let sparse = SparseGrid::from_vec2d(&dense, (-4, 10), 0);
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(*sparse.get(-3, 10), 2);
+
+        let (copied, origin) = sparse.to_vec2d().unwrap();
+        assert_eq!(origin, (-4, 10));
+        assert_eq!(copied, Vec2d::from_data(2, 2, vec![0, 1, 2, 0]));
+    }
+}
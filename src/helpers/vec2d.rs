@@ -1,5 +1,47 @@
-use super::offset::{Offset, self};
+use std::{collections::HashMap, convert::Infallible};
 
+use super::{direction::Directions, offset::{Offset, self}, vec2d_view::{Transform, Vec2dView, Vec2dViewMut}};
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellError<E> {
+    pub row: usize,
+    pub col: usize,
+    pub error: E
+}
+
+// Why a grid couldn't be parsed from strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError<E> {
+    // The mapper rejected a character
+    Cell(CellError<E>),
+    // A line with a different number of characters than the first one
+    Ragged { row: usize, expected: usize, found: usize },
+    Empty
+}
+
+impl<E> GridError<E> {
+    // For grids that start partway through the input, so rows line up with the input again
+    pub fn offset_rows(self, rows: usize) -> GridError<E> {
+        match self {
+            GridError::Cell(CellError { row, col, error }) => GridError::Cell(CellError { row: row + rows, col, error }),
+            GridError::Ragged { row, expected, found } => GridError::Ragged { row: row + rows, expected, found },
+            GridError::Empty => GridError::Empty
+        }
+    }
+}
+
+// Where the marker characters asked for while parsing were, each in reading order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markers {
+    positions: HashMap<char, Vec<(usize, usize)>>
+}
+
+impl Markers {
+    pub fn positions(&self, marker: char) -> &[(usize, usize)] {
+        self.positions.get(&marker).map_or(&[], |positions| positions.as_slice())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Vec2d<T> {
@@ -18,6 +60,16 @@ impl<T> Vec2d<T> {
         }
     }
 
+    // Data is laid out row by row
+    pub fn from_data(width: usize, height: usize, data: Vec<T>) -> Vec2d<T> {
+        assert_eq!(data.len(), width * height, "Grid data doesn't match its dimensions");
+        Vec2d {
+            width,
+            height,
+            data
+        }
+    }
+
     pub fn get(&self, row: usize, col: usize) -> &T {
         &self.data[row * self.width + col]
     }
@@ -52,7 +104,7 @@ impl<T> Vec2d<T> {
     }
     pub fn iter_col_mut(&mut self, col: usize) -> impl Iterator<Item=&mut T> {
         let w = self.width;
-        self.iter_mut().skip(col-1).step_by(w)
+        self.iter_mut().skip(col).step_by(w)
     }
 
     pub fn iter_cols(&self) -> impl Iterator<Item=impl Iterator<Item=&T>> {
@@ -71,27 +123,47 @@ impl<T> Vec2d<T> {
         positions.map(|(row, col)| self.get(row, col))
     }
 
+    // Parses one cell per character, giving None if the lines are ragged or there aren't any
     pub fn from_strings<It: Iterator<Item = String>>(strings: It, mapper: impl Fn(char) -> T) -> Option<Vec2d<T>> {
-        let mut width = 0;
+        Self::try_from_strings(strings, |c| Ok::<T, Infallible>(mapper(c))).ok()
+    }
+
+    // Like from_strings, but the mapper can reject characters.
+    // Every line must have as many characters as the first one.
+    pub fn try_from_strings<It: Iterator<Item = String>, E>(strings: It, mapper: impl Fn(char) -> Result<T, E>) -> Result<Vec2d<T>, GridError<E>> {
+        Self::try_from_strings_with_markers(strings, &[], mapper).map(|(grid, _)| grid)
+    }
+
+    // Also records where each of the `markers` characters was, like a start tile.
+    // Markers still go through the mapper, so it decides what ends up in the grid in their place.
+    pub fn try_from_strings_with_markers<It: Iterator<Item = String>, E>(strings: It, markers: &[char], mapper: impl Fn(char) -> Result<T, E>) -> Result<(Vec2d<T>, Markers), GridError<E>> {
+        let mut width = None;
         let mut height = 0;
         let mut data = Vec::new();
-        for line in strings {
-            width = line.len();
-            height += 1;
-            for c in line.chars() {
-                data.push(mapper(c));
+        let mut found = Markers::default();
+        for (row, line) in strings.enumerate() {
+            let mut line_width = 0;
+            for (col, c) in line.chars().enumerate() {
+                if markers.contains(&c) {
+                    found.positions.entry(c).or_default().push((row, col));
+                }
+                data.push(mapper(c).map_err(|error| GridError::Cell(CellError { row, col, error }))?);
+                line_width += 1;
             }
+
+            let expected = *width.get_or_insert(line_width);
+            if line_width != expected {
+                return Err(GridError::Ragged { row, expected, found: line_width });
+            }
+            height += 1;
         }
-        
+
+        let width = width.unwrap_or(0);
         if width == 0 || height == 0 {
-            return None;
+            return Err(GridError::Empty);
         }
 
-        Some(Vec2d {
-            width,
-            height,
-            data
-        })
+        Ok((Vec2d { width, height, data }, found))
     }
 
     pub fn offset_position(&self, row: usize, col: usize, offset: Offset) -> Option<(usize, usize)> {
@@ -105,6 +177,46 @@ impl<T> Vec2d<T> {
         }
     }
 
+    // Toroidal addressing, stepping off one edge comes back in on the opposite one
+    pub fn wrapping_offset_position(&self, row: usize, col: usize, offset: Offset) -> (usize, usize) {
+        (
+            (row as i64 + offset.rows).rem_euclid(self.height as i64) as usize,
+            (col as i64 + offset.cols).rem_euclid(self.width as i64) as usize
+        )
+    }
+    // Reads the grid as if it repeated forever in every direction, for puzzles on an infinitely tiled map
+    pub fn get_tiled(&self, row: i64, col: i64) -> &T {
+        self.get(row.rem_euclid(self.height as i64) as usize, col.rem_euclid(self.width as i64) as usize)
+    }
+
+    // In-bounds neighbours of a cell for any stencil, e.g. Offset::VON_NEUMANN or Offset::MOORE
+    pub fn neighbours<'a>(&'a self, row: usize, col: usize, stencil: &'a [Offset]) -> impl Iterator<Item=(usize, usize, &'a T)> + 'a {
+        stencil.iter()
+            .filter_map(move |offset| self.offset_position(row, col, *offset))
+            .map(move |(row, col)| (row, col, self.get(row, col)))
+    }
+    pub fn von_neumann_neighbours(&self, row: usize, col: usize) -> impl Iterator<Item=(usize, usize, &T)> {
+        self.neighbours(row, col, &Offset::VON_NEUMANN)
+    }
+    pub fn moore_neighbours(&self, row: usize, col: usize) -> impl Iterator<Item=(usize, usize, &T)> {
+        self.neighbours(row, col, &Offset::MOORE)
+    }
+
+    // Like neighbours, but wrapping around the edges, so there is always one per offset in the stencil
+    pub fn wrapping_neighbours<'a>(&'a self, row: usize, col: usize, stencil: &'a [Offset]) -> impl Iterator<Item=(usize, usize, &'a T)> + 'a {
+        stencil.iter()
+            .map(move |offset| self.wrapping_offset_position(row, col, *offset))
+            .map(move |(row, col)| (row, col, self.get(row, col)))
+    }
+
+    // One step in each of the given directions that stays in bounds, along with the direction taken
+    pub fn directional_neighbours(&self, row: usize, col: usize, directions: Directions) -> impl Iterator<Item=(usize, usize, Directions)> + '_ {
+        directions.iter().filter_map(move |direction| {
+            let (row, col) = self.offset_position(row, col, Offset::from_direction(direction))?;
+            Some((row, col, direction))
+        })
+    }
+
     pub fn is_in_bounds(&self, row: i64, col: i64) -> bool {
         row >= 0 && col >= 0 && row < self.height as i64 && col < self.width as i64
     }
@@ -118,4 +230,126 @@ impl<T> Vec2d<T> {
     pub fn enumerate(&self) -> impl Iterator<Item=(usize, usize, &T)> {
         self.data.iter().enumerate().map(move |(i, v)| (i / self.width, i % self.width, v))
     }
-}
\ No newline at end of file
+
+    // Zero-copy views, transform them to see the grid turned or mirrored
+    pub fn view(&self) -> Vec2dView<'_, T> {
+        Vec2dView::new(self, Transform::IDENTITY)
+    }
+    pub fn view_mut(&mut self) -> Vec2dViewMut<'_, T> {
+        Vec2dViewMut::new(self, Transform::IDENTITY)
+    }
+
+    // Copying versions of the view transforms
+    pub fn transposed(&self) -> Vec2d<T> where T: Clone {
+        self.view().transposed().to_vec2d()
+    }
+    pub fn rotated_cw(&self) -> Vec2d<T> where T: Clone {
+        self.view().rotated_cw().to_vec2d()
+    }
+    pub fn rotated_180(&self) -> Vec2d<T> where T: Clone {
+        self.view().rotated_180().to_vec2d()
+    }
+    pub fn rotated_ccw(&self) -> Vec2d<T> where T: Clone {
+        self.view().rotated_ccw().to_vec2d()
+    }
+    pub fn flipped_horizontally(&self) -> Vec2d<T> where T: Clone {
+        self.view().flipped_horizontally().to_vec2d()
+    }
+    pub fn flipped_vertically(&self) -> Vec2d<T> where T: Clone {
+        self.view().flipped_vertically().to_vec2d()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1 2 3
+    // 4 5 6
+    // 7 8 9
+    fn grid() -> Vec2d<u32> {
+        Vec2d::from_data(3, 3, (1..=9).collect())
+    }
+
+    fn values<'a>(neighbours: impl Iterator<Item=(usize, usize, &'a u32)>) -> Vec<u32> {
+        neighbours.map(|(_, _, v)| *v).collect()
+    }
+
+    #[test]
+    fn iterates_columns() {
+        let mut grid = grid();
+        assert_eq!(grid.iter_col(0).copied().collect::<Vec<_>>(), vec![1, 4, 7]);
+        for value in grid.iter_col_mut(0) {
+            *value *= 10;
+        }
+        assert_eq!(grid.iter_col(0).copied().collect::<Vec<_>>(), vec![10, 40, 70]);
+        assert_eq!(grid.iter_col_mut(2).map(|value| *value).collect::<Vec<_>>(), vec![3, 6, 9]);
+    }
+
+    #[test]
+    fn neighbours_stay_in_bounds() {
+        let grid = grid();
+        assert_eq!(values(grid.von_neumann_neighbours(1, 1)), vec![2, 6, 8, 4]);
+        assert_eq!(values(grid.von_neumann_neighbours(0, 0)), vec![2, 4]);
+        assert_eq!(values(grid.moore_neighbours(1, 1)), vec![1, 2, 3, 6, 9, 8, 7, 4]);
+        assert_eq!(values(grid.moore_neighbours(2, 2)), vec![5, 6, 8]);
+    }
+
+    #[test]
+    fn custom_stencils() {
+        let grid = grid();
+        let knight = [Offset::new(2, 1), Offset::new(1, 2), Offset::new(-2, -1)];
+        assert_eq!(values(grid.neighbours(0, 0, &knight)), vec![8, 6]);
+    }
+
+    #[test]
+    fn wrapping_neighbours_come_back_around() {
+        let grid = grid();
+        assert_eq!(values(grid.wrapping_neighbours(0, 0, &Offset::VON_NEUMANN)), vec![7, 2, 4, 3]);
+        assert_eq!(grid.wrapping_offset_position(2, 2, Offset::new(4, -5)), (0, 0));
+        assert_eq!(*grid.get_tiled(-1, 7), 8);
+    }
+
+    fn lines<'a>(lines: &'a [&str]) -> impl Iterator<Item=String> + 'a {
+        lines.iter().map(|line| line.to_string())
+    }
+
+    fn digit(c: char) -> Result<u32, String> {
+        c.to_digit(10).ok_or_else(|| format!("not a digit: {}", c))
+    }
+
+    #[test]
+    fn parses_grids() {
+        assert_eq!(Vec2d::try_from_strings(lines(&["123", "456", "789"]), digit), Ok(grid()));
+        // Width counts characters rather than bytes
+        assert_eq!(Vec2d::from_strings(lines(&["é.", ".é"]), |c| c).map(|grid| grid.width), Some(2));
+    }
+
+    #[test]
+    fn rejects_bad_grids() {
+        assert_eq!(Vec2d::try_from_strings(lines(&["123", "4x6"]), digit), Err(GridError::Cell(CellError { row: 1, col: 1, error: "not a digit: x".to_string() })));
+        assert_eq!(Vec2d::try_from_strings(lines(&["123", "45", "789"]), digit), Err(GridError::Ragged { row: 1, expected: 3, found: 2 }));
+        assert_eq!(Vec2d::try_from_strings(lines(&[]), digit), Err(GridError::Empty));
+        assert_eq!(Vec2d::from_strings(lines(&["12", "3"]), |c| c), None);
+    }
+
+    #[test]
+    fn finds_markers() {
+        let (grid, markers) = Vec2d::try_from_strings_with_markers(
+            lines(&["S..", "..E", "E.."]),
+            &['S', 'E'],
+            |c| Ok::<bool, String>(c != '.')
+        ).unwrap();
+        assert!(*grid.get(1, 2));
+        assert_eq!(markers.positions('S'), &[(0, 0)]);
+        assert_eq!(markers.positions('E'), &[(1, 2), (2, 0)]);
+        assert_eq!(markers.positions('X'), &[]);
+    }
+
+    #[test]
+    fn directional_neighbours_report_direction() {
+        let grid = grid();
+        let found = grid.directional_neighbours(0, 1, Directions::N | Directions::S | Directions::W).collect::<Vec<_>>();
+        assert_eq!(found, vec![(1, 1, Directions::S), (0, 0, Directions::W)]);
+    }
+}
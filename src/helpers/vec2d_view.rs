@@ -0,0 +1,253 @@
+use super::vec2d::Vec2d;
+
+// One of the 8 ways to lay a grid back down after turning and flipping it.
+// Maps a position as seen through the view back to a position in the underlying grid:
+// first the row and column are swapped if transposed, then either axis is mirrored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Transform {
+    transpose: bool,
+    flip_rows: bool,
+    flip_cols: bool
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { transpose: false, flip_rows: false, flip_cols: false };
+
+    // Rows become columns, i.e. mirrored along the main diagonal
+    pub fn transposed(self) -> Transform {
+        Transform { transpose: !self.transpose, ..self }
+    }
+    // Upside down, the first row becomes the last
+    pub fn flipped_vertically(self) -> Transform {
+        if self.transpose {
+            Transform { flip_cols: !self.flip_cols, ..self }
+        } else {
+            Transform { flip_rows: !self.flip_rows, ..self }
+        }
+    }
+    // Left to right, the first column becomes the last
+    pub fn flipped_horizontally(self) -> Transform {
+        if self.transpose {
+            Transform { flip_rows: !self.flip_rows, ..self }
+        } else {
+            Transform { flip_cols: !self.flip_cols, ..self }
+        }
+    }
+
+    // Clockwise, so the left column ends up as the top row
+    pub fn rotated_cw(self) -> Transform {
+        self.transposed().flipped_horizontally()
+    }
+    pub fn rotated_180(self) -> Transform {
+        self.flipped_vertically().flipped_horizontally()
+    }
+    // Counter-clockwise, so the right column ends up as the top row
+    pub fn rotated_ccw(self) -> Transform {
+        self.transposed().flipped_vertically()
+    }
+
+    // Height and width as seen through the transform, given those of the underlying grid
+    pub fn dimensions(&self, height: usize, width: usize) -> (usize, usize) {
+        if self.transpose {(width, height)} else {(height, width)}
+    }
+
+    pub fn source_position(&self, row: usize, col: usize, height: usize, width: usize) -> (usize, usize) {
+        let (row, col) = if self.transpose {(col, row)} else {(row, col)};
+        (
+            if self.flip_rows {height - 1 - row} else {row},
+            if self.flip_cols {width - 1 - col} else {col}
+        )
+    }
+}
+
+macro_rules! transform_methods {
+    () => {
+        pub fn transposed(self) -> Self {
+            Self { transform: self.transform.transposed(), ..self }
+        }
+        pub fn flipped_vertically(self) -> Self {
+            Self { transform: self.transform.flipped_vertically(), ..self }
+        }
+        pub fn flipped_horizontally(self) -> Self {
+            Self { transform: self.transform.flipped_horizontally(), ..self }
+        }
+        pub fn rotated_cw(self) -> Self {
+            Self { transform: self.transform.rotated_cw(), ..self }
+        }
+        pub fn rotated_180(self) -> Self {
+            Self { transform: self.transform.rotated_180(), ..self }
+        }
+        pub fn rotated_ccw(self) -> Self {
+            Self { transform: self.transform.rotated_ccw(), ..self }
+        }
+
+        pub fn height(&self) -> usize {
+            self.transform.dimensions(self.grid.height, self.grid.width).0
+        }
+        pub fn width(&self) -> usize {
+            self.transform.dimensions(self.grid.height, self.grid.width).1
+        }
+
+        fn source_position(&self, row: usize, col: usize) -> (usize, usize) {
+            self.transform.source_position(row, col, self.grid.height, self.grid.width)
+        }
+
+        pub fn is_in_bounds(&self, row: i64, col: i64) -> bool {
+            row >= 0 && col >= 0 && row < self.height() as i64 && col < self.width() as i64
+        }
+
+        // Copies the grid out the way it looks through the view
+        pub fn to_vec2d(&self) -> Vec2d<T> where T: Clone {
+            let positions = (0..self.height()).flat_map(|row| (0..self.width()).map(move |col| (row, col)));
+            let data = positions.map(|(row, col)| self.get(row, col).clone()).collect();
+            Vec2d::from_data(self.width(), self.height(), data)
+        }
+    };
+}
+
+// A zero-copy, read only look at a Vec2d through a Transform.
+// Cheap to copy around, so all the transforms take it by value and hand back a new view.
+#[derive(Debug)]
+pub struct Vec2dView<'a, T> {
+    grid: &'a Vec2d<T>,
+    transform: Transform
+}
+
+impl<T> Clone for Vec2dView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Vec2dView<'_, T> {}
+
+impl<'a, T> Vec2dView<'a, T> {
+    pub fn new(grid: &'a Vec2d<T>, transform: Transform) -> Vec2dView<'a, T> {
+        Vec2dView { grid, transform }
+    }
+
+    transform_methods!();
+
+    pub fn get(&self, row: usize, col: usize) -> &'a T {
+        let (row, col) = self.source_position(row, col);
+        self.grid.get(row, col)
+    }
+    pub fn try_get(&self, row: i64, col: i64) -> Option<&'a T> {
+        if self.is_in_bounds(row, col) {
+            Some(self.get(row as usize, col as usize))
+        } else {
+            None
+        }
+    }
+
+    pub fn iter_row(&self, row: usize) -> impl Iterator<Item=&'a T> + 'a {
+        let view = *self;
+        (0..self.width()).map(move |col| view.get(row, col))
+    }
+    pub fn iter_rows(&self) -> impl Iterator<Item=impl Iterator<Item=&'a T> + 'a> + 'a {
+        let view = *self;
+        (0..self.height()).map(move |row| view.iter_row(row))
+    }
+
+    pub fn iter_col(&self, col: usize) -> impl Iterator<Item=&'a T> + 'a {
+        let view = *self;
+        (0..self.height()).map(move |row| view.get(row, col))
+    }
+    pub fn iter_cols(&self) -> impl Iterator<Item=impl Iterator<Item=&'a T> + 'a> + 'a {
+        let view = *self;
+        (0..self.width()).map(move |col| view.iter_col(col))
+    }
+
+    pub fn enumerate(&self) -> impl Iterator<Item=(usize, usize, &'a T)> + 'a {
+        let view = *self;
+        (0..self.height()).flat_map(move |row| (0..view.width()).map(move |col| (row, col, view.get(row, col))))
+    }
+}
+
+// Same as Vec2dView, but writes go through to the underlying grid
+#[derive(Debug)]
+pub struct Vec2dViewMut<'a, T> {
+    grid: &'a mut Vec2d<T>,
+    transform: Transform
+}
+
+impl<'a, T> Vec2dViewMut<'a, T> {
+    pub fn new(grid: &'a mut Vec2d<T>, transform: Transform) -> Vec2dViewMut<'a, T> {
+        Vec2dViewMut { grid, transform }
+    }
+
+    transform_methods!();
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        let (row, col) = self.source_position(row, col);
+        self.grid.get(row, col)
+    }
+    pub fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let (row, col) = self.source_position(row, col);
+        self.grid.get_mut(row, col)
+    }
+    pub fn set(&mut self, row: usize, col: usize, value: T) {
+        let (row, col) = self.source_position(row, col);
+        self.grid.set(row, col, value);
+    }
+
+    // A read only view of the same thing, for while this one is borrowed
+    pub fn as_view(&self) -> Vec2dView<'_, T> {
+        Vec2dView::new(self.grid, self.transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1 2 3
+    // 4 5 6
+    fn grid() -> Vec2d<u32> {
+        Vec2d::from_data(3, 2, vec![1, 2, 3, 4, 5, 6])
+    }
+
+    fn rows(view: Vec2dView<u32>) -> Vec<Vec<u32>> {
+        view.iter_rows().map(|row| row.copied().collect()).collect()
+    }
+
+    #[test]
+    fn transforms_views() {
+        let grid = grid();
+        let view = grid.view();
+        assert_eq!(rows(view), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(rows(view.transposed()), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(rows(view.rotated_cw()), vec![vec![4, 1], vec![5, 2], vec![6, 3]]);
+        assert_eq!(rows(view.rotated_180()), vec![vec![6, 5, 4], vec![3, 2, 1]]);
+        assert_eq!(rows(view.rotated_ccw()), vec![vec![3, 6], vec![2, 5], vec![1, 4]]);
+        assert_eq!(rows(view.flipped_horizontally()), vec![vec![3, 2, 1], vec![6, 5, 4]]);
+        assert_eq!(rows(view.flipped_vertically()), vec![vec![4, 5, 6], vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn composes_transforms() {
+        let grid = grid();
+        let view = grid.view();
+        assert_eq!(rows(view.rotated_cw().rotated_cw()), rows(view.rotated_180()));
+        assert_eq!(rows(view.rotated_cw().rotated_ccw()), rows(view));
+        assert_eq!(rows(view.rotated_cw().flipped_horizontally()), rows(view.transposed()));
+        assert_eq!(view.rotated_ccw().rotated_ccw().rotated_ccw().rotated_ccw().transform, Transform::IDENTITY);
+    }
+
+    #[test]
+    fn copies_match_views() {
+        let grid = grid();
+        assert_eq!(grid.rotated_cw(), grid.view().rotated_cw().to_vec2d());
+        assert_eq!(grid.rotated_cw().width, 2);
+        assert_eq!(grid.rotated_cw().rotated_ccw(), grid);
+        assert_eq!(grid.transposed().transposed(), grid);
+    }
+
+    #[test]
+    fn writes_through_mutable_views() {
+        let mut grid = grid();
+        grid.view_mut().rotated_cw().set(0, 0, 40);
+        assert_eq!(*grid.get(1, 0), 40);
+        *grid.view_mut().flipped_horizontally().get_mut(0, 0) = 30;
+        assert_eq!(*grid.get(0, 2), 30);
+    }
+}
@@ -0,0 +1,9 @@
+extern crate bitflags;
+
+// The grid and geometry helpers are meant to be reused outside of the puzzles,
+// the rest is what the CLI is built from
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod helpers;
+pub mod solvers;
@@ -1,20 +1,100 @@
-extern crate bitflags;
-use std::path::PathBuf;
+use std::{path::PathBuf, ops::RangeInclusive, process::ExitCode};
 
 use clap::Parser;
-use solvers::solve_day;
-
-mod solvers;
-pub mod helpers;
+use advent_2023::{answers::RecordedAnswers, bench::bench_day, error, solvers::{list_solvers, solve_day, solve_days}};
 
 #[derive(Parser)]
 struct Cli {
     #[arg(short='d', long="day", default_value_t=String::from("5p1"))]
     day: String,
+    /// List every day and part that has a solver, then exit
+    #[arg(long="list", exclusive=true)]
+    list: bool,
+    /// Run every registered solver against its default input and print a summary
+    #[arg(long="all", conflicts_with="days")]
+    all: bool,
+    /// Like --all, but only for a range of days, e.g. 5..12
+    #[arg(long="days", value_parser=parse_day_range)]
+    days: Option<RangeInclusive<u32>>,
+    /// How many solvers to run at once with --all or --days, defaults to one per CPU
+    #[arg(short='j', long="jobs", value_name="N", value_parser=clap::value_parser!(u64).range(1..))]
+    jobs: Option<u64>,
+    /// Compare answers against the ones recorded in the answers file and fail on a mismatch
+    #[arg(long="verify")]
+    verify: bool,
+    #[arg(long="answers", default_value="answers.toml")]
+    answers: PathBuf,
+    /// Run the solver N times on an in-memory copy of the input and report timing statistics
+    #[arg(long="bench", value_name="N", value_parser=clap::value_parser!(u64).range(1..), conflicts_with_all=["all", "days", "verify"])]
+    bench: Option<u64>,
+    /// Also write the benchmark results as JSON to this file
+    #[arg(long="bench-json", value_name="PATH", requires="bench")]
+    bench_json: Option<PathBuf>,
+    /// Puzzle input to use instead of inputs/dayNpM.txt, or - to read it from stdin
     input: Option<PathBuf>
 }
 
-fn main() {
-    let args = Cli::parse();
-    solve_day(args.day, args.input);
+// Accepts "7", "5..12" and "5..=12". Both range forms include the last day.
+fn parse_day_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let parse_day = |d: &str| d.trim().parse::<u32>().map_err(|_| format!("Invalid day: '{d}'"));
+
+    match s.split_once("..") {
+        Some((start, end)) => {
+            let (start, end) = (parse_day(start)?, parse_day(end.trim_start_matches('='))?);
+            if start > end {
+                return Err(format!("Day range {start}..{end} is backwards"));
+            }
+            Ok(start..=end)
+        },
+        None => {
+            let day = parse_day(s)?;
+            Ok(day..=day)
+        }
+    }
+}
+
+// Returns false if a solver failed or an answer didn't match the recorded one
+fn run(args: Cli) -> error::Result<bool> {
+    if args.list {
+        list_solvers();
+        return Ok(true);
+    }
+
+    if let Some(runs) = args.bench {
+        bench_day(args.day, args.input, runs as usize, args.bench_json.as_deref())?;
+        return Ok(true);
+    }
+
+    let answers = if args.verify {Some(RecordedAnswers::load(&args.answers)?)} else {None};
+
+    if args.all || args.days.is_some() {
+        Ok(solve_days(args.days, answers.as_ref(), args.jobs.map(|jobs| jobs as usize)))
+    } else {
+        solve_day(args.day, args.input, answers.as_ref())
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_day_range;
+
+    #[test]
+    fn parses_day_ranges() {
+        assert_eq!(parse_day_range("5..12"), Ok(5..=12));
+        assert_eq!(parse_day_range("5..=12"), Ok(5..=12));
+        assert_eq!(parse_day_range("7"), Ok(7..=7));
+        assert!(parse_day_range("12..5").is_err());
+        assert!(parse_day_range("x..5").is_err());
+    }
 }
@@ -1,67 +1,384 @@
-use std::{collections::HashMap, path::PathBuf, fs::File, io::BufReader};
-
-mod  day1p1; mod  day1p2;
-mod  day2p1; mod  day2p2;
-mod  day3p1; mod  day3p2;
-mod  day4p1; mod  day4p2;
-mod  day5p1; mod  day5p2;
-mod  day6p1; mod  day6p2;
-mod  day7p1; mod  day7p2;
-mod  day8p1; mod  day8p2;
-mod  day9p1; mod  day9p2;
-mod day10p1; mod day10p2;
-mod day11p1; mod day11p2;
-mod day12p1; mod day12p2;
-mod day13p1; mod day13p2;
-mod day14p1; mod day14p2;
-mod day15p1; mod day15p2;
-mod day16p1; mod day16p2;
-mod day17p1; mod day17p2;
-
-struct Solver {
-    solve: fn(input: BufReader<File>) -> ()
-}
-
-pub fn solve_day(day: String, input: Option<PathBuf>) {
-    let solvers: HashMap<String, Solver> = HashMap::from([
-        (String::from( "1p1"),  day1p1::SOLVER), (String::from( "1p2"),  day1p2::SOLVER),
-        (String::from( "2p1"),  day2p1::SOLVER), (String::from( "2p2"),  day2p2::SOLVER),
-        (String::from( "3p1"),  day3p1::SOLVER), (String::from( "3p2"),  day3p2::SOLVER),
-        (String::from( "4p1"),  day4p1::SOLVER), (String::from( "4p2"),  day4p2::SOLVER),
-        (String::from( "5p1"),  day5p1::SOLVER), (String::from( "5p2"),  day5p2::SOLVER),
-        (String::from( "6p1"),  day6p1::SOLVER), (String::from( "6p2"),  day6p2::SOLVER),
-        (String::from( "7p1"),  day7p1::SOLVER), (String::from( "7p2"),  day7p2::SOLVER),
-        (String::from( "8p1"),  day8p1::SOLVER), (String::from( "8p2"),  day8p2::SOLVER),
-        (String::from( "9p1"),  day9p1::SOLVER), (String::from( "9p2"),  day9p2::SOLVER),
-        (String::from("10p1"), day10p1::SOLVER), (String::from("10p2"), day10p2::SOLVER),
-        (String::from("11p1"), day11p1::SOLVER), (String::from("11p2"), day11p2::SOLVER),
-        (String::from("12p1"), day12p1::SOLVER), (String::from("12p2"), day12p2::SOLVER),
-        (String::from("13p1"), day13p1::SOLVER), (String::from("13p2"), day13p2::SOLVER),
-        (String::from("14p1"), day14p1::SOLVER), (String::from("14p2"), day14p2::SOLVER),
-        (String::from("15p1"), day15p1::SOLVER), (String::from("15p2"), day15p2::SOLVER),
-        (String::from("16p1"), day16p1::SOLVER), (String::from("16p2"), day16p2::SOLVER),
-        (String::from("17p1"), day17p1::SOLVER), (String::from("17p2"), day17p2::SOLVER),
-    ]);
-
-    let current_solver = solvers.get(&day).unwrap_or_else(|| panic!("Not a valid day to solve: {day}"));
-
-    let path = input.unwrap_or_else(|| {
-        let work_dir = std::env::current_dir().expect("Solver should be called with a path or a working dir set");
-        work_dir.join(format!("inputs/day{day}.txt"))
-    });
+use std::{collections::HashMap, path::{Path, PathBuf}, fs::{self, File}, io::{self, BufRead, BufReader, Read}, fmt::Display, iter, ops::RangeInclusive, time::{Duration, Instant}, cell::Cell};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::{answers::{RecordedAnswers, Verdict}, error::{Error, Result}, helpers::vec2d::{Markers, Vec2d}};
+
+// Module declarations and the REGISTERED table, one entry per src/solvers/dayNpM.rs, ordered by day and part.
+// Generated by build.rs, so there is nothing to edit here when adding a day.
+include!(concat!(env!("OUT_DIR"), "/solvers.rs"));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerValue {
+    Number(i128),
+    Text(String)
+}
+
+macro_rules! answer_value_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for AnswerValue {
+            fn from(value: $t) -> Self {
+                AnswerValue::Number(value as i128)
+            }
+        })*
+    };
+}
+answer_value_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for AnswerValue {
+    fn from(value: String) -> Self {
+        AnswerValue::Text(value)
+    }
+}
+impl From<&str> for AnswerValue {
+    fn from(value: &str) -> Self {
+        AnswerValue::Text(value.to_string())
+    }
+}
+
+impl Display for AnswerValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswerValue::Number(n) => write!(f, "{}", n),
+            AnswerValue::Text(s) => write!(f, "{}", s)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub value: AnswerValue,
+    pub label: Option<&'static str>
+}
+
+impl Answer {
+    pub fn labelled(label: &'static str, value: impl Into<AnswerValue>) -> Answer {
+        Answer {
+            value: value.into(),
+            label: Some(label)
+        }
+    }
+}
+
+impl<T: Into<AnswerValue>> From<T> for Answer {
+    fn from(value: T) -> Self {
+        Answer {
+            value: value.into(),
+            label: None
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.label {
+            Some(label) => write!(f, "{}: {}", label, self.value),
+            None => write!(f, "{}", self.value)
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Solver {
+    solve: fn(input: &mut dyn BufRead) -> Result<Answer>
+}
 
-    let file = File::open(path.clone()).unwrap_or_else(|_| panic!("Cannot open input file: {}", path.display()));
-    
-    let reader = BufReader::new(file);
+impl Solver {
+    // Takes any buffered reader, so in-memory inputs work too, e.g. `solver.solve(&mut "1abc2".as_bytes())`
+    pub fn solve(&self, input: &mut dyn BufRead) -> Result<Answer> {
+        (self.solve)(input)
+    }
+}
+
+thread_local! {
+    static PARSED_AT: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+// Solvers call this once they're done parsing their input, so timings can be split into parse and solve.
+// Solvers that parse lazily as they go just don't call it.
+pub fn mark_parsed() {
+    PARSED_AT.with(|parsed_at| parsed_at.set(Some(Instant::now())));
+}
+
+pub struct Timing {
+    pub parse: Option<Duration>,
+    pub total: Duration
+}
+
+impl Timing {
+    pub fn solve(&self) -> Option<Duration> {
+        self.parse.map(|parse| self.total - parse)
+    }
+}
+
+pub struct RegisteredSolver {
+    pub day: u32,
+    pub part: u32,
+    pub solver: Solver
+}
+
+impl RegisteredSolver {
+    // The "5p1" style key used on the command line and in the answers file
+    pub fn key(&self) -> String {
+        format!("{}p{}", self.day, self.part)
+    }
+}
+
+// Runs a solver against an inline sample input, for the per-day tests
+#[cfg(test)]
+pub(crate) fn solve_sample(solver: &Solver, sample: &str) -> AnswerValue {
+    solver.solve(&mut sample.as_bytes()).expect("Sample input should solve").value
+}
+
+pub fn registered_solvers() -> &'static [RegisteredSolver] {
+    REGISTERED
+}
+
+pub fn all_solvers() -> HashMap<String, Solver> {
+    REGISTERED.iter().map(|entry| (entry.key(), entry.solver)).collect()
+}
+
+// Prints every available day along with its parts
+pub fn list_solvers() {
+    for entries in REGISTERED.chunk_by(|a, b| a.day == b.day) {
+        println!("Day {:>2}: {}", entries[0].day, entries.iter().map(|entry| entry.key()).join(", "));
+    }
+}
+
+// Relative to the working directory
+pub fn default_input_path(day: &str) -> PathBuf {
+    PathBuf::from(format!("inputs/day{day}.txt"))
+}
+
+pub fn find_solver<'a>(solvers: &'a HashMap<String, Solver>, day: &str) -> Result<&'a Solver> {
+    solvers.get(day).ok_or_else(|| Error::UnknownDay(day.to_string()))
+}
 
-    use std::time::Instant;
+// Reads every line up front, so parsers can work on plain strings and IO errors surface in one place
+pub(crate) fn read_lines(input: &mut dyn BufRead) -> Result<Vec<String>> {
+    input.lines().map(|line| line.map_err(Error::from)).collect()
+}
+
+// Parses each line on its own, tagging any parse error with the line it came from
+pub(crate) fn parse_lines<T>(input: &mut dyn BufRead, parse: impl Fn(String) -> Result<T>) -> Result<Vec<T>> {
+    input.lines()
+        .enumerate()
+        .map(|(i, line)| parse(line?).map_err(|e| e.on_line(i)))
+        .collect()
+}
+
+// Builds a grid with one cell per character, for the days whose input is a character map.
+// A character the mapper rejects becomes a parse error pointing at that cell.
+pub(crate) fn parse_char_grid<T>(lines: impl Iterator<Item = String>, mapper: impl Fn(char) -> std::result::Result<T, String>) -> Result<Vec2d<T>> {
+    Ok(Vec2d::try_from_strings(lines, mapper)?)
+}
+
+// Same, but also finds where the marker characters are, like a start tile
+pub(crate) fn parse_char_grid_with_markers<T>(lines: impl Iterator<Item = String>, markers: &[char], mapper: impl Fn(char) -> std::result::Result<T, String>) -> Result<(Vec2d<T>, Markers)> {
+    Ok(Vec2d::try_from_strings_with_markers(lines, markers, mapper)?)
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> Error + '_ {
+    move |source| Error::Io {
+        path: if is_stdin(path) {None} else {Some(path.to_path_buf())},
+        source
+    }
+}
+
+// Opens a puzzle input for reading, with "-" standing in for stdin
+pub fn open_input(path: &Path) -> Result<Box<dyn BufRead>> {
+    if is_stdin(path) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file = File::open(path).map_err(io_error(path))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+// Reads a whole puzzle input into memory, with "-" standing in for stdin
+pub fn read_input(path: &Path) -> Result<Vec<u8>> {
+    if is_stdin(path) {
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data).map_err(io_error(path))?;
+        return Ok(data);
+    }
+    fs::read(path).map_err(io_error(path))
+}
+
+pub fn run_solver(solver: &Solver, reader: &mut dyn BufRead) -> Result<(Answer, Timing)> {
+    PARSED_AT.with(|parsed_at| parsed_at.set(None));
     let now = Instant::now();
 
-    {
-        (current_solver.solve)(reader);
+    let answer = solver.solve(reader)?;
+
+    let total = now.elapsed();
+    let parse = PARSED_AT.with(|parsed_at| parsed_at.get()).map(|parsed_at| parsed_at - now);
+
+    Ok((answer, Timing { parse, total }))
+}
+
+// Returns false if the answer was checked against a recorded one and did not match
+pub fn solve_day(day: String, input: Option<PathBuf>, answers: Option<&RecordedAnswers>) -> Result<bool> {
+    let solvers = all_solvers();
+
+    let current_solver = find_solver(&solvers, &day)?;
+
+    let path = input.unwrap_or_else(|| default_input_path(&day));
+
+    let mut reader = open_input(&path)?;
+
+    let (answer, timing) = run_solver(current_solver, &mut reader)?;
+
+    println!("{}", answer);
+    println!("Elapsed: {:.2?}", timing.total);
+
+    let verdict = answers.map(|answers| answers.check(&day, &answer));
+    if let Some(verdict) = &verdict {
+        println!("Verified: {}", verdict);
     }
 
-    let elapsed = now.elapsed();
-    println!("Elapsed: {:.2?}", elapsed);
-    
+    Ok(!matches!(verdict, Some(Verdict::Mismatch(_))))
+}
+
+struct SummaryRow {
+    day: u32,
+    part: u32,
+    answer: String,
+    elapsed: String,
+    verdict: Option<String>
+}
+
+fn print_summary(rows: &[SummaryRow], with_verdicts: bool) {
+    let headers = ["Day", "Part", "Answer", "Elapsed", "Verified"];
+    let columns = if with_verdicts {headers.len()} else {headers.len() - 1};
+    let headers = &headers[..columns];
+
+    let cells = rows.iter()
+        .map(|row| vec![
+            row.day.to_string(),
+            row.part.to_string(),
+            row.answer.clone(),
+            row.elapsed.clone(),
+            row.verdict.clone().unwrap_or_default()
+        ])
+        .map(|mut line| { line.truncate(columns); line })
+        .collect::<Vec<_>>();
+
+    let widths = (0..headers.len())
+        .map(|i| cells.iter().map(|c| c[i].len()).chain(iter::once(headers[i].len())).max().unwrap())
+        .collect::<Vec<_>>();
+
+    let print_line = |line: &[String]| {
+        let padded = line.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width));
+        println!("{}", padded.collect::<Vec<_>>().join(" | ").trim_end());
+    };
+
+    print_line(&headers.iter().map(|h| h.to_string()).collect::<Vec<_>>());
+    println!("{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"));
+    for line in &cells {
+        print_line(line);
+    }
+}
+
+// Runs every registered solver (optionally only for a range of days) against its default input.
+// Solvers run concurrently on up to `jobs` threads, but the summary is still printed in day order.
+// Returns false if any solver failed, or if an answer was checked against a recorded one and did not match
+pub fn solve_days(days: Option<RangeInclusive<u32>>, answers: Option<&RecordedAnswers>, jobs: Option<usize>) -> bool {
+    let selected = REGISTERED.iter()
+        .filter(|entry| days.as_ref().is_none_or(|range| range.contains(&entry.day)))
+        .collect::<Vec<_>>();
+
+    // Zero threads lets rayon pick, which is one per CPU
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()
+        .expect("Should be able to start the solver thread pool");
+
+    let start = Instant::now();
+    // Collecting an indexed parallel iterator keeps the results in day order
+    let outcomes = pool.install(|| {
+        selected.par_iter().map(|entry| run_registered(entry, answers)).collect::<Vec<_>>()
+    });
+    let wall_clock = start.elapsed();
+
+    let cpu_time = outcomes.iter().filter_map(|outcome| outcome.elapsed).sum::<Duration>();
+    let all_match = outcomes.iter().all(|outcome| outcome.ok);
+    let rows = outcomes.into_iter().map(|outcome| outcome.row).collect::<Vec<_>>();
+
+    print_summary(&rows, answers.is_some());
+    println!();
+    println!("Wall-clock time: {:.2?}", wall_clock);
+    println!("CPU time (summed over solvers): {:.2?}", cpu_time);
+
+    all_match
+}
+
+struct DayOutcome {
+    row: SummaryRow,
+    // Only set when the solver actually ran to completion
+    elapsed: Option<Duration>,
+    // False if the solver failed or its answer didn't match the recorded one
+    ok: bool
+}
+
+fn run_registered(entry: &RegisteredSolver, answers: Option<&RecordedAnswers>) -> DayOutcome {
+    let (day, part, key) = (entry.day, entry.part, entry.key());
+    let path = default_input_path(&key);
+
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        // Only a missing input is a clean skip, anything else wrong with it fails the day like a solver error would
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return DayOutcome {
+                row: SummaryRow {
+                    day,
+                    part,
+                    answer: String::from("(no input)"),
+                    elapsed: String::from("-"),
+                    verdict: None
+                },
+                elapsed: None,
+                ok: true
+            };
+        },
+        Err(error) => return failed_outcome(day, part, io_error(&path)(error))
+    };
+
+    match run_solver(&entry.solver, &mut BufReader::new(file)) {
+        Ok((answer, timing)) => {
+            let elapsed = timing.total;
+            let verdict = answers.map(|answers| answers.check(&key, &answer));
+
+            DayOutcome {
+                ok: !matches!(verdict, Some(Verdict::Mismatch(_))),
+                row: SummaryRow {
+                    day,
+                    part,
+                    answer: answer.value.to_string(),
+                    elapsed: format!("{:.2?}", elapsed),
+                    verdict: verdict.map(|v| v.to_string())
+                },
+                elapsed: Some(elapsed)
+            }
+        },
+        Err(error) => failed_outcome(day, part, error)
+    }
+}
+
+fn failed_outcome(day: u32, part: u32, error: Error) -> DayOutcome {
+    DayOutcome {
+        row: SummaryRow {
+            day,
+            part,
+            answer: format!("error: {}", error),
+            elapsed: String::from("-"),
+            verdict: None
+        },
+        elapsed: None,
+        ok: false
+    }
 }
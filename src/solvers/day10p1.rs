@@ -1,9 +1,10 @@
 use core::panic;
-use std::{io::{BufRead, BufReader}, fs::File, ops::{Sub, Add}, fmt::Display};
+use std::{io::BufRead, ops::{Sub, Add}, fmt::Display, iter};
 
 use bitflags::bitflags;
 
-use super::Solver;
+use crate::{error::{Error, Result}, helpers::{search, vec2d::Vec2d}};
+use super::{mark_parsed, parse_char_grid_with_markers, read_lines, Answer, Solver};
 
 
 bitflags! {
@@ -31,7 +32,7 @@ struct PipeNode {
     directions: Directions,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
 struct Coord(usize, usize);
 impl Add for Coord {
     type Output = Coord;
@@ -54,121 +55,120 @@ impl Display for Coord {
 }
 
 struct PipeMap {
-    nodes: Vec<Vec<PipeNode>>,
+    nodes: Vec2d<PipeNode>,
     start: Coord
 }
 
-fn parse_pipe_map(input: BufReader<File>) -> PipeMap {
-    let mut nodes = Vec::new();
-    let mut start = None;
-    for (row, line) in input.lines().enumerate() {
-        let mut node_row = Vec::new();
-        for (col, c) in line.unwrap_or_default().chars().enumerate() {
-            let mut directions = Directions::empty();
-            if c == '|' {
-                directions |= Directions::N | Directions::S;
-            } else if c == '-' {
-                directions |= Directions::E | Directions::W;
-            } else if c == 'L' {
-                directions |= Directions::N | Directions::E;
-            } else if c == 'J' {
-                directions |= Directions::N | Directions::W;
-            } else if c == '7' {
-                directions |= Directions::S | Directions::W;
-            } else if c == 'F' {
-                directions |= Directions::S | Directions::E;
-            } else if c == 'S' {
-                directions |= Directions::all();
-                start = Some(Coord(row, col));
-            }
-            node_row.push(PipeNode {
-                directions
-            });
-        }
-        nodes.push(node_row);
-    }
-    PipeMap {
-        nodes,
-        start: start.expect("Expected start node to exist")
-    }
+fn parse_pipe_map(input: &mut dyn BufRead) -> Result<PipeMap> {
+    let (nodes, markers) = parse_char_grid_with_markers(read_lines(input)?.into_iter(), &['S'], |c| {
+        let directions = match c {
+            '|' => Directions::N | Directions::S,
+            '-' => Directions::E | Directions::W,
+            'L' => Directions::N | Directions::E,
+            'J' => Directions::N | Directions::W,
+            '7' => Directions::S | Directions::W,
+            'F' => Directions::S | Directions::E,
+            'S' => Directions::all(),
+            '.' => Directions::empty(),
+            _ => return Err(format!("invalid pipe tile '{}'", c))
+        };
+        Ok(PipeNode { directions })
+    })?;
+
+    let start = match markers.positions('S') {
+        [(row, col)] => Coord(*row, *col),
+        [] => return Err(Error::parse("map has no start tile")),
+        [_, (row, col), ..] => return Err(Error::parse_at(*row, *col, "map has more than one start tile"))
+    };
+    Ok(PipeMap { nodes, start })
 }
 
 fn follow_direction(map: &PipeMap, location: Coord, direction: Directions) -> Option<Coord> {
     match direction {
         Directions::N => if location.0 == 0 {None} else {Some(location - Coord(1, 0))},
-        Directions::S => if location.0 == map.nodes.len() - 1 {None} else {Some(location + Coord(1, 0))},
+        Directions::S => if location.0 == map.nodes.height - 1 {None} else {Some(location + Coord(1, 0))},
         Directions::W => if location.1 == 0 {None} else {Some(location - Coord(0, 1))},
-        Directions::E => if location.1 == map.nodes[0].len() - 1 {None} else {Some(location + Coord(0, 1))},
+        Directions::E => if location.1 == map.nodes.width - 1 {None} else {Some(location + Coord(0, 1))},
         _ => None
     }
 }
 
 fn is_valid_way(map: &PipeMap, location: Coord, direction: Directions) -> bool {
-    let curr = map.nodes[location.0][location.1];
+    let curr = *map.nodes.get(location.0, location.1);
 
     if !curr.directions.contains(direction) {
         return false;
     }
 
     if let Some(next) = follow_direction(map, location, direction) {
-        return map.nodes[next.0][next.1].directions.contains(opposite_direction(direction))
+        return map.nodes.get(next.0, next.1).directions.contains(opposite_direction(direction))
     }
 
     return false;
 }
 
-struct DfsEntry {
-    coord: Coord,
-    coming_from: Directions,
-    remaining_directions: Directions
+// The pipes connected to a tile that connect back to it as well
+fn connected(map: &PipeMap, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+    map.nodes.get(coord.0, coord.1).directions.iter()
+        .filter(move |d| is_valid_way(map, coord, *d))
+        .filter_map(move |d| follow_direction(map, coord, d))
 }
 
-fn find_loop(map: &PipeMap) -> Vec<Coord> {
-    
-    let mut path_stack = vec![DfsEntry {
-        coord: map.start,
-        coming_from: Directions::empty(),
-        remaining_directions: 
-            map.nodes[map.start.0][map.start.1].directions.iter().filter(|d| is_valid_way(map, map.start, *d)).collect()
-    }];
-
-    while let Some(entry) = path_stack.pop() {
-        if path_stack.len() > 1 && entry.coord.eq(&map.start) {
-            return path_stack.iter().map(|item| item.coord).collect()
-        }
-
-        if let Some(next_dir) = entry.remaining_directions.iter().next() {
-            path_stack.push(DfsEntry { 
-                coord: entry.coord, 
-                coming_from: entry.coming_from,
-                remaining_directions: entry.remaining_directions.difference(next_dir) 
-            });
-            let next = follow_direction(map, entry.coord, next_dir).unwrap();
-            path_stack.push(DfsEntry {
-                coord: next,
-                coming_from: next_dir,
-                remaining_directions: map.nodes[next.0][next.1]
-                    .directions.iter()
-                    .filter(|d| !opposite_direction(*d).eq(&next_dir) && is_valid_way(map, next, *d))
-                    .collect()
-            })
+// Searches from one of the pipes leading out of the start tile round to another one, without passing back through the start.
+// Every other tile only has two connections, so the path it finds is the rest of the loop.
+fn find_loop(map: &PipeMap) -> Result<Vec<Coord>> {
+    let exits = connected(map, map.start).collect::<Vec<_>>();
+    for (i, first) in exits.iter().enumerate() {
+        let others = &exits[i + 1..];
+        let search = search::bfs(
+            [*first],
+            |coord| connected(map, *coord).filter(|next| *next != map.start).collect::<Vec<_>>(),
+            |coord| others.contains(coord)
+        );
+        if let Some(path) = search.goal_path() {
+            return Ok(iter::once(map.start).chain(path).collect());
         }
     }
 
-    panic!("No loop found");
+    Err(Error::NoSolution("no loop passes through the start tile".to_string()))
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let map = parse_pipe_map(input);
+        let map = parse_pipe_map(input)?;
+        mark_parsed();
 
-        let l = find_loop(&map);
+        let l = find_loop(&map)?;
 
         let total_len = l.len() + 1; // Accounting coming back to the start
 
         let furthest = total_len/2 + total_len%2 - 1;
 
-        println!("Furthest steps: {}", furthest);
+        Ok(Answer::labelled("Furthest steps", furthest))
 
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(8));
+    }
+
+    #[test]
+    fn ignores_dead_ends_off_the_start() {
+        let sample = ".|...\n-S-7.\n.|.|.\n.L-J.";
+        assert_eq!(solve_sample(&SOLVER, sample), AnswerValue::from(4));
+    }
+}
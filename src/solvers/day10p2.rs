@@ -1,10 +1,10 @@
 use core::panic;
-use std::{io::{BufRead, BufReader}, fs::File, ops::{Sub, Add}, fmt::Display, collections::HashSet};
+use std::{io::BufRead, ops::{Sub, Add}, fmt::Display, collections::HashSet, iter};
 
 use bitflags::bitflags;
-use num::Integer;
 
-use super::Solver;
+use crate::{error::{Error, Result}, helpers::{search, vec2d::Vec2d}};
+use super::{mark_parsed, parse_char_grid_with_markers, read_lines, Answer, Solver};
 
 
 bitflags! {
@@ -56,56 +56,45 @@ impl Display for Coord {
 }
 
 struct PipeMap {
-    nodes: Vec<Vec<PipeNode>>,
+    nodes: Vec2d<PipeNode>,
     start: Coord
 }
 impl PipeMap {
     fn get(&self, coord: Coord) -> &PipeNode {
-        &self.nodes[coord.0][coord.1]
+        self.nodes.get(coord.0, coord.1)
     }
 }
 
-fn parse_pipe_map(input: BufReader<File>) -> PipeMap {
-    let mut nodes = Vec::new();
-    let mut start = None;
-    for (row, line) in input.lines().enumerate() {
-        let mut node_row = Vec::new();
-        for (col, c) in line.unwrap_or_default().chars().enumerate() {
-            let mut directions = Directions::empty();
-            if c == '|' {
-                directions |= Directions::N | Directions::S;
-            } else if c == '-' {
-                directions |= Directions::E | Directions::W;
-            } else if c == 'L' {
-                directions |= Directions::N | Directions::E;
-            } else if c == 'J' {
-                directions |= Directions::N | Directions::W;
-            } else if c == '7' {
-                directions |= Directions::S | Directions::W;
-            } else if c == 'F' {
-                directions |= Directions::S | Directions::E;
-            } else if c == 'S' {
-                directions |= Directions::all();
-                start = Some(Coord(row, col));
-            }
-            node_row.push(PipeNode {
-                directions
-            });
-        }
-        nodes.push(node_row);
-    }
-    PipeMap {
-        nodes,
-        start: start.expect("Expected start node to exist")
-    }
+fn parse_pipe_map(input: &mut dyn BufRead) -> Result<PipeMap> {
+    let (nodes, markers) = parse_char_grid_with_markers(read_lines(input)?.into_iter(), &['S'], |c| {
+        let directions = match c {
+            '|' => Directions::N | Directions::S,
+            '-' => Directions::E | Directions::W,
+            'L' => Directions::N | Directions::E,
+            'J' => Directions::N | Directions::W,
+            '7' => Directions::S | Directions::W,
+            'F' => Directions::S | Directions::E,
+            'S' => Directions::all(),
+            '.' => Directions::empty(),
+            _ => return Err(format!("invalid pipe tile '{}'", c))
+        };
+        Ok(PipeNode { directions })
+    })?;
+
+    let start = match markers.positions('S') {
+        [(row, col)] => Coord(*row, *col),
+        [] => return Err(Error::parse("map has no start tile")),
+        [_, (row, col), ..] => return Err(Error::parse_at(*row, *col, "map has more than one start tile"))
+    };
+    Ok(PipeMap { nodes, start })
 }
 
 fn follow_direction(map: &PipeMap, location: Coord, direction: Directions) -> Option<Coord> {
     match direction {
         Directions::N => if location.0 == 0 {None} else {Some(location - Coord(1, 0))},
-        Directions::S => if location.0 == map.nodes.len() - 1 {None} else {Some(location + Coord(1, 0))},
+        Directions::S => if location.0 == map.nodes.height - 1 {None} else {Some(location + Coord(1, 0))},
         Directions::W => if location.1 == 0 {None} else {Some(location - Coord(0, 1))},
-        Directions::E => if location.1 == map.nodes[0].len() - 1 {None} else {Some(location + Coord(0, 1))},
+        Directions::E => if location.1 == map.nodes.width - 1 {None} else {Some(location + Coord(0, 1))},
         _ => None
     }
 }
@@ -124,115 +113,91 @@ fn is_valid_way(map: &PipeMap, location: Coord, direction: Directions) -> bool {
     return false;
 }
 
-struct DfsEntry {
-    coord: Coord,
-    coming_from: Directions,
-    remaining_directions: Directions
+// The pipes connected to a tile that connect back to it as well
+fn connected(map: &PipeMap, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+    map.get(coord).directions.iter()
+        .filter(move |d| is_valid_way(map, coord, *d))
+        .filter_map(move |d| follow_direction(map, coord, d))
 }
 
-fn find_loop(map: &PipeMap) -> Vec<Coord> {
-    
-    let mut path_stack = vec![DfsEntry {
-        coord: map.start,
-        coming_from: Directions::empty(),
-        remaining_directions: 
-            map.get(map.start).directions.iter().filter(|d| is_valid_way(map, map.start, *d)).collect()
-    }];
-
-    while let Some(entry) = path_stack.pop() {
-        if path_stack.len() > 1 && entry.coord.eq(&map.start) {
-            return path_stack.iter().map(|item| item.coord).collect()
-        }
-
-        if let Some(next_dir) = entry.remaining_directions.iter().next() {
-            path_stack.push(DfsEntry { 
-                coord: entry.coord, 
-                coming_from: entry.coming_from,
-                remaining_directions: entry.remaining_directions.difference(next_dir) 
-            });
-            let next = follow_direction(map, entry.coord, next_dir).unwrap();
-            path_stack.push(DfsEntry {
-                coord: next,
-                coming_from: next_dir,
-                remaining_directions: map.get(next)
-                    .directions.iter()
-                    .filter(|d| !opposite_direction(*d).eq(&next_dir) && is_valid_way(map, next, *d))
-                    .collect()
-            })
+// Searches from one of the pipes leading out of the start tile round to another one, without passing back through the start.
+// Every other tile only has two connections, so the path it finds is the rest of the loop.
+fn find_loop(map: &PipeMap) -> Result<Vec<Coord>> {
+    let exits = connected(map, map.start).collect::<Vec<_>>();
+    for (i, first) in exits.iter().enumerate() {
+        let others = &exits[i + 1..];
+        let search = search::bfs(
+            [*first],
+            |coord| connected(map, *coord).filter(|next| *next != map.start).collect::<Vec<_>>(),
+            |coord| others.contains(coord)
+        );
+        if let Some(path) = search.goal_path() {
+            return Ok(iter::once(map.start).chain(path).collect());
         }
     }
 
-    panic!("No loop found");
+    Err(Error::NoSolution("no loop passes through the start tile".to_string()))
 }
 
-fn is_space_inside_loop(map: &PipeMap, location: Coord, loop_set: &HashSet<Coord>) -> bool {
-    
-    // Enclosed by the loop does not mean on the edge
-    if loop_set.contains(&location) {
-        return false;
-    }
-
-    let items_to_test = vec![
-        (Directions::N, location.0),
-        (Directions::S, map.nodes.len() - location.0 - 1),
-        (Directions::W, location.1),
-        (Directions::E, map.nodes[0].len() - location.1 - 1)
-    ];
-
-    // This is such a pointless optimisattion btw
-    let (direction, _) = items_to_test.iter().min_by_key(|item| item.1).unwrap();
-
-    let line_dirs = *direction | opposite_direction(*direction);
-
-    let perp_dirs = Directions::all() ^ line_dirs;
-
-    let mut loop_intersections = 0;
-    let mut curr = location;
-    let mut curr_path_segment = None;
-    while let Some(next) = follow_direction(map, curr, *direction) {
-        if !is_valid_way(map, curr, *direction) {
-            if let Some(path_segment) = curr_path_segment {
-                let total_loop_dirs = map.get(path_segment).directions | map.get(curr).directions;
-                if total_loop_dirs & perp_dirs == perp_dirs {
-                    loop_intersections += 1;
-                }
-                curr_path_segment = None;
-            }
-
-            if loop_set.contains(&next) {
-                curr_path_segment = Some(next);
-            }
-        }
-        curr = next;
-    }
-    if let Some(path_segment) = curr_path_segment {
-        let total_loop_dirs = map.get(path_segment).directions | map.get(curr).directions;
-        if total_loop_dirs & perp_dirs == perp_dirs {
-            loop_intersections += 1;
+// Works at double resolution so the outside can squeeze between pipes that run side by side.
+// Each tile's 2x2 block is the tile itself, then the gaps to its east, south and south-east,
+// and only the loop's own pipes and the connections between them are walls.
+fn count_enclosed(map: &PipeMap, loop_set: &HashSet<Coord>) -> usize {
+    // The start tile connects every way, but only two of those are actually part of the loop
+    let start_directions = map.get(map.start).directions.iter()
+        .filter(|d| is_valid_way(map, map.start, *d))
+        .collect::<Directions>();
+
+    let components = map.nodes.connected_components_2x(|row, col, node| {
+        let location = Coord(row, col);
+        if !loop_set.contains(&location) {
+            return [[true, true], [true, true]];
         }
-    }
-    
-    return loop_intersections.is_odd();
+        let directions = if location == map.start {start_directions} else {node.directions};
+        [[false, !directions.contains(Directions::E)], [!directions.contains(Directions::S), true]]
+    });
+
+    components.labels.iter()
+        .flatten()
+        .filter(|label| !components.touches_edge[**label])
+        .count()
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let map = parse_pipe_map(input);
+        let map = parse_pipe_map(input)?;
+        mark_parsed();
 
-        let l = find_loop(&map);
+        let l = find_loop(&map)?;
 
         let loop_set = l.iter().cloned().collect::<HashSet<Coord>>();
 
-        let all_locations = (0..map.nodes.len()).flat_map(|row| {
-            (0..map.nodes[0].len()).map(move |col| Coord(row, col))
-        });
+        let total_enclosed = count_enclosed(&map, &loop_set);
 
-        let enclosed = all_locations.filter(|location| is_space_inside_loop(&map, *location, &loop_set));
+        Ok(Answer::labelled("Enclosed", total_enclosed))
 
-        // println!("Enclosed: {}", enclosed.clone().join("\n"));
-        let total_enclosed = enclosed.count(); 
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
 
-        println!("Enclosed: {}", total_enclosed);
+    const SAMPLE: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
 
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(8));
     }
-};
\ No newline at end of file
+}
@@ -1,6 +1,12 @@
-use std::{io::{BufRead, BufReader}, fs::File, collections::HashSet, cmp::{min, max}};
+use std::io::BufRead;
 
-use super::Solver;
+use itertools::Itertools;
+
+use crate::{error::Result, helpers::{compressed_grid::{CompressedAxis, CompressedGrid}, vec2d::Vec2d}};
+use super::{mark_parsed, parse_char_grid, read_lines, Answer, Solver};
+
+// How many rows or columns each empty one turns into
+const EXPANSION: i64 = 2;
 
 #[derive(PartialEq, Eq)]
 enum MapEntry {
@@ -8,107 +14,70 @@ enum MapEntry {
     Galaxy
 }
 
-struct GalaxyMap {
-    map: Vec<Vec<MapEntry>>
-}
-impl GalaxyMap {
-    fn width(&self) -> usize {
-        self.map[0].len()
-    }
-    
-    fn empty_rows(&self) -> HashSet<usize> {
-        let mut empty_rows = HashSet::new();
-        for (i, row) in self.map.iter().enumerate() {
-            if row.iter().all(|entry| *entry == MapEntry::Empty) {
-                empty_rows.insert(i);
-            }
-        }
-        return empty_rows;
-    }
-    fn empty_cols(&self) -> HashSet<usize> {
-        let mut empty_cols = HashSet::new();
-        for col in 0..self.width() {
-            if self.map.iter().all(|row| row[col] == MapEntry::Empty) {
-                empty_cols.insert(col);
-            }
-        }
-        return empty_cols;
-    }
-
-    fn galaxies(&self) -> HashSet<(usize, usize)> {
-        let mut galaxies = HashSet::new();
-        for (y, row) in self.map.iter().enumerate() {
-            for (x, entry) in row.iter().enumerate() {
-                if *entry == MapEntry::Galaxy {
-                    galaxies.insert((x, y));
-                }
-            }
-        }
-        return galaxies;
-    }
+type GalaxyMap = CompressedGrid<MapEntry>;
 
-    fn shortest_path(&self, start: (usize, usize), end: (usize, usize), empty_rows: &HashSet<usize>, empty_cols: &HashSet<usize>) -> usize {
-        let mut cost = 0;
-        
-        for row in min(start.0, end.0)+1..=max(start.0, end.0) {
-            if empty_cols.contains(&row) {
-                cost += 2;
-            } else {
-                cost += 1;
-            }
-        }
-        for col in min(start.1, end.1)+1..=max(start.1, end.1) {
-            if empty_rows.contains(&col) {
-                cost += 2;
-            } else {
-                cost += 1;
-            }
-        }
-
-        cost
-    }
+fn band_weight<'a>(mut line: impl Iterator<Item=&'a MapEntry>) -> i64 {
+    if line.all(|entry| *entry == MapEntry::Empty) {EXPANSION} else {1}
+}
 
-    fn total_shortest_galaxy_paths_cost(&self) -> usize {
-        let empty_rows = self.empty_rows();
-        let empty_cols = self.empty_cols();
-        let galaxies = self.galaxies();
-        let galaxy_vec = galaxies.iter().collect::<Vec<_>>();
+// The universe is never actually expanded, empty rows and columns just weigh more
+fn expand(map: Vec2d<MapEntry>) -> GalaxyMap {
+    let rows = CompressedAxis::from_weights(0, map.iter_rows().map(band_weight));
+    let cols = CompressedAxis::from_weights(0, map.iter_cols().map(band_weight));
+    CompressedGrid::new(map, rows, cols)
+}
 
-        let mut cost = 0;
-        for (i, (x, y)) in galaxy_vec.iter().enumerate() {
-            for (x2, y2) in galaxy_vec[i+1..].iter() {
-                cost += self.shortest_path((*x, *y), (*x2, *y2), &empty_rows, &empty_cols);
-            }
-        }
+fn total_shortest_galaxy_paths_cost(map: &GalaxyMap) -> i64 {
+    let galaxies = map.grid.enumerate()
+        .filter(|(_, _, entry)| **entry == MapEntry::Galaxy)
+        .map(|(row, col, _)| (row, col))
+        .collect::<Vec<_>>();
 
-        cost
-    }
+    galaxies.iter()
+        .tuple_combinations()
+        .map(|(a, b)| map.distance(*a, *b))
+        .sum()
+}
 
-    fn from_input(input: BufReader<File>) -> GalaxyMap {
-        let map = input.lines().map(|line| {
-            let bytes = line.unwrap().into_bytes();
-            let map_row = bytes.iter().map(|c| {
-                match c {
-                    b'.' => MapEntry::Empty,
-                    b'#' => MapEntry::Galaxy,
-                    _ => panic!("Invalid map entry")
-                }
-            }).collect();
-            return map_row;
-        }).collect();
-
-        return GalaxyMap {
-            map
-        };
-    }
+fn parse_galaxy_map(input: &mut dyn BufRead) -> Result<GalaxyMap> {
+    let map = parse_char_grid(read_lines(input)?.into_iter(), |c| match c {
+        '.' => Ok(MapEntry::Empty),
+        '#' => Ok(MapEntry::Galaxy),
+        _ => Err(format!("invalid map entry '{}'", c))
+    })?;
+    Ok(expand(map))
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let map = GalaxyMap::from_input(input);
+        let map = parse_galaxy_map(input)?;
+        mark_parsed();
 
-        let shortest = map.total_shortest_galaxy_paths_cost();
+        let shortest = total_shortest_galaxy_paths_cost(&map);
 
-        println!("Shortest: {}", shortest);
+        Ok(Answer::labelled("Shortest", shortest))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(374));
+    }
+}
@@ -1,8 +1,9 @@
-use std::{io::BufRead, str::FromStr};
+use std::str::FromStr;
 
 use itertools::Itertools;
 
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, parse_lines, Answer, Solver};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum SpringState {
@@ -16,23 +17,21 @@ struct SpringRow {
     damaged_sequences: Vec<usize>
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseSpringRowErr;
-
 impl FromStr for SpringRow {
-    type Err = ParseSpringRowErr;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (states_str, sequences_str) = s.split_whitespace().collect_tuple().ok_or(ParseSpringRowErr)?;
+    fn from_str(s: &str) -> Result<Self> {
+        let (states_str, sequences_str) = s.split_whitespace().collect_tuple()
+            .ok_or_else(|| Error::parse("expected spring states followed by damaged group sizes"))?;
 
-        let states = states_str.chars().map(|c| match c {
-            '.' => SpringState::Operational,
-            '#' => SpringState::Damaged,
-            '?' => SpringState::Unknown,
-            _ => panic!("Invalid spring state")
-        }).collect();
+        let states = states_str.chars().enumerate().map(|(col, c)| match c {
+            '.' => Ok(SpringState::Operational),
+            '#' => Ok(SpringState::Damaged),
+            '?' => Ok(SpringState::Unknown),
+            _ => Err(Error::parse_at_column(col, format!("invalid spring state '{}'", c)))
+        }).collect::<Result<_>>()?;
 
-        let damaged_sequences = sequences_str.split(',').map(|seq| seq.parse::<usize>().unwrap()).collect();
+        let damaged_sequences = sequences_str.split(',').map(|seq| Ok(seq.parse::<usize>()?)).collect::<Result<_>>()?;
 
         Ok(SpringRow {
             states,
@@ -55,7 +54,10 @@ fn possible_damaged_arrangements(row: &SpringRow) -> usize {
     let known_errors = row.states.iter().filter(|state| **state == SpringState::Damaged).count();
     let total_errors: usize = row.damaged_sequences.iter().sum();
 
-    let errors_needed = total_errors - known_errors;
+    // More springs are already damaged than the groups allow for, nothing can fit
+    let Some(errors_needed) = total_errors.checked_sub(known_errors) else {
+        return 0;
+    };
 
     let valid_sequences = unknown_indices.iter().combinations(errors_needed).filter(|seq| {
         let new_states = row.states
@@ -81,11 +83,49 @@ fn possible_damaged_arrangements(row: &SpringRow) -> usize {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let spring_rows = input.lines().map(|line| line.unwrap().parse::<SpringRow>().unwrap());
+        let spring_rows = parse_lines(input, |line| line.parse::<SpringRow>())?.into_iter();
+        mark_parsed();
 
 
         let possible_arrangements: usize = spring_rows.map(|r| possible_damaged_arrangements(&r)).sum();
 
-        println!("{}", possible_arrangements);
+        Ok(Answer::from(possible_arrangements))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, solvers::{solve_sample, AnswerValue}};
+    use super::{SpringRow, SpringState, SOLVER};
+
+    const SAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(21));
     }
-};
\ No newline at end of file
+
+    #[test]
+    fn parses_spring_row() {
+        let row = "#.? 1,12".parse::<SpringRow>().unwrap();
+        assert_eq!(row.states, vec![SpringState::Damaged, SpringState::Operational, SpringState::Unknown]);
+        assert_eq!(row.damaged_sequences, vec![1, 12]);
+    }
+
+    #[test]
+    fn rejects_invalid_spring_state() {
+        let error = "#.x? 1".parse::<SpringRow>().err().unwrap();
+        assert!(matches!(error, Error::Parse { column: Some(3), .. }));
+    }
+
+    #[test]
+    fn rejects_row_without_groups() {
+        assert!("#.?".parse::<SpringRow>().is_err());
+    }
+}
@@ -1,11 +1,12 @@
-use std::{io::BufRead, str::FromStr, collections::HashMap, cmp::min};
+use std::{str::FromStr, collections::HashMap, cmp::min};
 
 use itertools::Itertools;
 
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{parse_lines, mark_parsed, Answer, Solver};
 
 fn expand<T: Copy>(coll: Vec<T>, times: usize) -> Vec<T> {
-    (0..times).map(|_| coll.clone()).flatten().collect()
+    (0..times).flat_map(|_| coll.clone()).collect()
 }
 fn expand_sep<T: Copy>(coll: Vec<T>, times: usize, separator: T) -> Vec<T> {
     Itertools::intersperse((0..times).map(|_| coll.clone()), vec![separator]).flatten().collect()
@@ -24,24 +25,22 @@ struct SpringRow {
     damaged_sequences: Vec<usize>
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseSpringRowErr;
-
 const REPEATS: usize = 5;
 impl FromStr for SpringRow {
-    type Err = ParseSpringRowErr;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (states_str, sequences_str) = s.split_whitespace().collect_tuple().ok_or(ParseSpringRowErr)?;
+    fn from_str(s: &str) -> Result<Self> {
+        let (states_str, sequences_str) = s.split_whitespace().collect_tuple()
+            .ok_or_else(|| Error::parse("expected spring states followed by damaged group sizes"))?;
 
-        let states: Vec<SpringState> = states_str.chars().map(|c| match c {
-            '.' => SpringState::Operational,
-            '#' => SpringState::Damaged,
-            '?' => SpringState::Unknown,
-            _ => panic!("Invalid spring state")
-        }).collect();
+        let states: Vec<SpringState> = states_str.chars().enumerate().map(|(col, c)| match c {
+            '.' => Ok(SpringState::Operational),
+            '#' => Ok(SpringState::Damaged),
+            '?' => Ok(SpringState::Unknown),
+            _ => Err(Error::parse_at_column(col, format!("invalid spring state '{}'", c)))
+        }).collect::<Result<_>>()?;
 
-        let damaged_sequences = sequences_str.split(',').map(|seq| seq.parse::<usize>().unwrap()).collect();
+        let damaged_sequences = sequences_str.split(',').map(|seq| Ok(seq.parse::<usize>()?)).collect::<Result<_>>()?;
 
         Ok(SpringRow {
             states: expand_sep(states, REPEATS, SpringState::Unknown),
@@ -70,7 +69,7 @@ fn valid_damaged_arrangements(row: &SpringRow) -> usize {
             return *count;
         }
 
-        if row.damaged_sequences.len() == 0 {
+        if row.damaged_sequences.is_empty() {
             return if row.states.iter().all(|s| *s != SpringState::Damaged) {1} else {0};
         }
         if row.damaged_sequences[0] > row.states.len() {
@@ -101,10 +100,30 @@ fn valid_damaged_arrangements(row: &SpringRow) -> usize {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let spring_rows = input.lines().map(|line| line.unwrap().parse::<SpringRow>().unwrap()).collect::<Vec<_>>();
+        let spring_rows = parse_lines(input, |line| line.parse::<SpringRow>())?;
+        mark_parsed();
 
-        let total = spring_rows.iter().map(|r| valid_damaged_arrangements(r)).sum::<usize>();
+        let total = spring_rows.iter().map(valid_damaged_arrangements).sum::<usize>();
 
-        println!("{}", total);
+        Ok(Answer::from(total))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(525152));
     }
-};
\ No newline at end of file
+}
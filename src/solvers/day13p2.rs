@@ -1,10 +1,10 @@
-use std::{io::BufRead, cmp::min};
+use std::cmp::min;
 
 use itertools::Itertools;
 
-use crate::helpers::vec2d::Vec2d;
+use crate::{error::{Error, Result}, helpers::{vec2d::{GridError, Vec2d}, vec2d_view::Vec2dView}};
 
-use super::Solver;
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum TileType {
@@ -15,27 +15,44 @@ enum TileType {
 type Grid = Vec2d<TileType>;
 
 
-fn parse_grid<It: Iterator<Item=String>>(input: &mut It) -> Option<Grid> {
-    Grid::from_strings(
-        input.take_while(|line| !line.is_empty()),
+fn parse_grid<It: Iterator<Item=(usize, String)>>(input: &mut It) -> Result<Option<Grid>> {
+    // Grids are stacked one after the other, so errors need to be shifted to where this one starts
+    let mut first_line = None;
+    let lines = input
+        .take_while(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            first_line.get_or_insert(idx);
+            line
+        });
+    let grid = Grid::try_from_strings(
+        lines,
         |c| match c {
-            '#' => TileType::Rock,
-            _ => TileType::Ash
+            '#' => Ok(TileType::Rock),
+            '.' => Ok(TileType::Ash),
+            _ => Err(format!("invalid tile '{}'", c))
         }
-    )
+    );
+    // Running out of lines is how the caller knows there are no grids left
+    match grid {
+        Ok(grid) => Ok(Some(grid)),
+        Err(GridError::Empty) => Ok(None),
+        Err(e) => Err(Error::from(e.offset_rows(first_line.unwrap_or(0))))
+    }
 }
 
-fn parse_all_grids<It: Iterator<Item=String>>(input: &mut It) -> Vec<Grid> {
+fn parse_all_grids<It: Iterator<Item=(usize, String)>>(input: &mut It) -> Result<Vec<Grid>> {
     let mut grids = Vec::new();
-    while let Some(grid) = parse_grid(input) {
+    while let Some(grid) = parse_grid(input)? {
         grids.push(grid);
     }
-    grids
+    Ok(grids)
 }
 
-fn get_horizontal_differences<'a>(grid: &'a Grid) -> impl Iterator<Item = usize> + 'a {
-    (1..grid.height).map(move |refl_bottom| {
-        let to_check = min(refl_bottom, grid.height - refl_bottom);
+// For every possible reflection line between rows, how many cells differ from their mirror image.
+// Columns are handled by transposing the view.
+fn get_row_differences<'a>(grid: Vec2dView<'a, TileType>) -> impl Iterator<Item = usize> + 'a {
+    (1..grid.height()).map(move |refl_bottom| {
+        let to_check = min(refl_bottom, grid.height() - refl_bottom);
         (0..to_check).map(move |offset| {
             grid.iter_row(refl_bottom -1 - offset)
             .zip(grid.iter_row(refl_bottom + offset))
@@ -45,23 +62,11 @@ fn get_horizontal_differences<'a>(grid: &'a Grid) -> impl Iterator<Item = usize>
     })
 }
 
-fn get_vertical_differences<'a>(grid: &'a Grid) -> impl Iterator<Item = usize> + 'a {
-    (1..grid.width).map(move |refl_right| {
-        let to_check = min(refl_right, grid.width - refl_right);
-        (0..to_check).map(move |offset| {
-            grid.iter_col(refl_right -1 - offset)
-            .zip(grid.iter_col(refl_right + offset))
-            .filter(|(a, b)| !a.eq(b))
-            .count()
-        }).sum()
-    })
-}
-
 fn get_smudged_reflection_index(grid: &Grid) -> usize {
-    if let Some(hor) = get_horizontal_differences(grid).find_position(|diff| *diff == 1) {
+    if let Some(hor) = get_row_differences(grid.view()).find_position(|diff| *diff == 1) {
         return (hor.0 + 1) * 100;
     }
-    if let Some(ver) = get_vertical_differences(grid).find_position(|diff| *diff == 1) {
+    if let Some(ver) = get_row_differences(grid.view().transposed()).find_position(|diff| *diff == 1) {
         return ver.0 + 1;
     }
     return 0;
@@ -69,12 +74,41 @@ fn get_smudged_reflection_index(grid: &Grid) -> usize {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let mut input = input.lines().map(|line| line.unwrap());
+        let mut input = read_lines(input)?.into_iter().enumerate();
 
-        let grids = parse_all_grids(&mut input);
+        let grids = parse_all_grids(&mut input)?;
+        mark_parsed();
 
-        let total = grids.iter().map(|grid| get_smudged_reflection_index(grid)).sum::<usize>();
+        let total = grids.iter().map(get_smudged_reflection_index).sum::<usize>();
 
-        println!("Total: {}", total);
+        Ok(Answer::labelled("Total", total))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.##..##.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(400));
+    }
+}
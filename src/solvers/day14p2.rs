@@ -1,8 +1,7 @@
-use std::{io::BufRead, collections::HashMap};
+use crate::helpers::{cycle, vec2d::Vec2d, vec2d_view::Vec2dViewMut};
 
-use crate::helpers::vec2d::Vec2d;
-
-use super::Solver;
+use crate::error::Result;
+use super::{mark_parsed, parse_char_grid, read_lines, Answer, Solver};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 enum DishTile {
@@ -13,66 +12,36 @@ enum DishTile {
 
 type Dish = Vec2d<DishTile>;
 
-fn parse_dish<It: Iterator<Item=String>>(input: &mut It) -> Option<Dish> {
-    Dish::from_strings(
+fn parse_dish<It: Iterator<Item=String>>(input: &mut It) -> Result<Dish> {
+    parse_char_grid(
         input,
         |c| match c {
-            '.' => DishTile::Empty,
-            '#' => DishTile::Static,
-            'O' => DishTile::Rolling,
-            _ => panic!("Invalid dish tile")
+            '.' => Ok(DishTile::Empty),
+            '#' => Ok(DishTile::Static),
+            'O' => Ok(DishTile::Rolling),
+            _ => Err(format!("invalid dish tile '{}'", c))
         }
     )
 }
 
-fn roll(dish: &mut Dish, row: usize, col: usize, delta_rows: i64, delta_cols: i64) {
-    let mut curr_row = row as i64;
-    let mut curr_col = col as i64;
-    loop {
-        let next_row: i64 = curr_row + delta_rows;
-        let next_col:i64 = curr_col + delta_cols;
-        if !dish.is_in_bounds(next_row, next_col) {
-            break;
-        }
-        if let Some(kind) = dish.try_get(next_row, next_col) {
-            if !kind.eq(&DishTile::Empty) {
-                break;
-            }
-        }
-        curr_row = next_row;
-        curr_col = next_col;
+fn roll_north(dish: &mut Vec2dViewMut<DishTile>, row: usize, col: usize) {
+    let mut curr_row = row;
+    while curr_row > 0 && *dish.get(curr_row - 1, col) == DishTile::Empty {
+        curr_row -= 1;
     }
-    if curr_row != row as i64 || curr_col != col as i64 {
-        dish.set(curr_row as usize, curr_col as usize, DishTile::Rolling);
-        dish.set(row as usize, col as usize, DishTile::Empty);
+    if curr_row != row {
+        dish.set(curr_row, col, DishTile::Rolling);
+        dish.set(row, col, DishTile::Empty);
     }
 }
 
-fn tilt(dish: &mut Dish, delta_rows: i64, delta_cols: i64) {
-    let start_left = delta_cols < 0;
-    let start_top = delta_rows < 0;
-
-    let rows = {
-        let mut r = (0..dish.height).collect::<Vec<_>>();
-        if !start_top {
-            r.reverse();
-        }
-        r
-    };
-
-    let cols = {
-        let mut c = (0..dish.width).collect::<Vec<_>>();
-        if !start_left {
-            c.reverse();
-        }
-        c
-    };
-
-    for row in rows {
-        for col in cols.clone() {
-            if dish.get(row, col).eq(&DishTile::Rolling) {
-                roll(dish, row, col, delta_rows, delta_cols);
-            }   
+// Only ever tilts towards the top of the view, other directions rotate the view so they end up on top
+fn tilt_north(mut dish: Vec2dViewMut<DishTile>) {
+    for row in 0..dish.height() {
+        for col in 0..dish.width() {
+            if *dish.get(row, col) == DishTile::Rolling {
+                roll_north(&mut dish, row, col);
+            }
         }
     }
 }
@@ -87,45 +56,45 @@ fn calculate_load(dish: &Dish) -> usize {
 
 
 fn spin_cycle(dish: &mut Dish) {
-    tilt(dish, -1, 0);
-    tilt(dish, 0, -1);
-    tilt(dish, 1, 0);
-    tilt(dish, 0, 1);
-}
-
-fn spin_iteration(dish: &Dish, iterations: usize) -> Dish {
-    let mut memo = HashMap::new();
-
-    let mut next = dish.clone();
-    for it in 0..iterations {
-        let curr = next.clone();
-        let previously_seen_at = memo.get(&curr);
-        if let Some(prev) = previously_seen_at {
-            let cycle_length = it - prev;
-            let remaining_iterations = (iterations - it - 1) % cycle_length;
-            
-            for _ in 0..remaining_iterations {
-                spin_cycle(&mut next);
-            }
-            return next;
-        }
-        
-        memo.insert(curr, it);
-        
-        spin_cycle(&mut next);
-    }
-    next
+    tilt_north(dish.view_mut());
+    tilt_north(dish.view_mut().rotated_cw());
+    tilt_north(dish.view_mut().rotated_180());
+    tilt_north(dish.view_mut().rotated_ccw());
 }
 
 const CYCLES: usize = 1000000000;
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let dish = parse_dish(&mut input.lines().map(|line| line.unwrap())).unwrap();
+        let dish = parse_dish(&mut read_lines(input)?.into_iter())?;
+        mark_parsed();
 
-        let spun = spin_iteration(&dish, CYCLES);
+        let spun = cycle::nth_state(dish, spin_cycle, CYCLES);
         
         let load = calculate_load(&spun);
-        println!("Load: {}", load);
+        Ok(Answer::labelled("Load", load))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(64));
+    }
+}
@@ -1,8 +1,7 @@
-use std::io::BufRead;
-
 use itertools::Itertools;
 
-use super::Solver;
+use crate::error::Error;
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 fn ascii_hash(input: &str) -> usize {
     input.bytes().fold(0, |acc, b| {
@@ -10,20 +9,35 @@ fn ascii_hash(input: &str) -> usize {
     })
 }
 
-fn parse_instructions<'a>(input: &'a String) -> Vec<&'a str> {
+fn parse_instructions(input: &String) -> Vec<&str> {
     input.split(",").collect()
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let line = input.lines().next().expect("Expecting a line of input here").unwrap();
+        let line = read_lines(input)?.into_iter().next().ok_or_else(|| Error::parse("expected a line of input"))?;
         
         let instructions = parse_instructions(&line);
+        mark_parsed();
 
         let hashes = instructions.iter().map(|s| ascii_hash(s)).collect_vec();
 
         let total: usize = hashes.iter().sum();
 
-        println!("Total: {}", total);
+        Ok(Answer::labelled("Total", total))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(1320));
     }
-};
\ No newline at end of file
+}
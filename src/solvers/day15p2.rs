@@ -1,8 +1,9 @@
-use std::{io::{BufRead, Error}, str::FromStr};
+use std::str::FromStr;
 
 use linked_hash_map::LinkedHashMap;
 
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, read_lines, Answer, Solver};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -14,15 +15,15 @@ enum Operation {
 impl FromStr for Operation {
     type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.chars().next().unwrap() {
-            '-' => Ok(Operation::Remove),
-            '=' => if let Ok(lens) = s[1..].parse() {
+    fn from_str(s: &str) -> Result<Self> {
+        match s.chars().next() {
+            Some('-') => Ok(Operation::Remove),
+            Some('=') => if let Ok(lens) = s[1..].parse() {
                 Ok(Operation::Place(lens))
             } else {
-                Err(self::Error::other("Invalid lens number"))
+                Err(Error::parse(format!("invalid lens number in '{}'", s)))
             },
-            _ => Err(self::Error::other("Invalid operation"))
+            _ => Err(Error::parse(format!("invalid operation '{}'", s)))
         }
     }
 }
@@ -39,13 +40,13 @@ lazy_static! {
 impl FromStr for Instruction {
     type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         let captures = INSTRUCTION_REGEX.captures(s);
         match captures {
-            None => Err(self::Error::other("Invalid instruction")),
+            None => Err(Error::parse(format!("invalid instruction '{}'", s))),
             Some(captures) => {
-                let label = captures.name("label").unwrap().as_str().to_string();
-                let operation = captures.name("operation").unwrap().as_str().parse();
+                let label = captures["label"].to_string();
+                let operation = captures["operation"].parse();
 
                 match operation {
                     Err(e) => Err(e),
@@ -68,8 +69,13 @@ fn ascii_hash(input: &str) -> usize {
     })
 }
 
-fn parse_instructions(input: &String) -> Vec<Instruction> {
-    input.split(",").map(|s| s.parse().unwrap()).collect()
+fn parse_instructions(input: &String) -> Result<Vec<Instruction>> {
+    let mut column = 0;
+    input.split(",").map(|s| {
+        let instruction = s.parse().map_err(|e: Error| e.on_column(column).on_line(0));
+        column += s.len() + 1;
+        instruction
+    }).collect()
 }
 
 fn compute_lenses(instructions: &Vec<Instruction>) -> Vec<LinkedHashMap<&String, usize>> {
@@ -107,14 +113,48 @@ fn focusing_power(boxes: &Vec<LinkedHashMap<&String, usize>>) -> usize {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let line = input.lines().next().expect("Expecting a line of input here").unwrap();
+        let line = read_lines(input)?.into_iter().next().ok_or_else(|| Error::parse("expected a line of input"))?;
         
-        let instructions = parse_instructions(&line);
+        let instructions = parse_instructions(&line)?;
+        mark_parsed();
 
         let boxes = compute_lenses(&instructions);
         
         let power = focusing_power(&boxes);
 
-        println!("Power: {}", power);
+        Ok(Answer::labelled("Power", power))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, solvers::{solve_sample, AnswerValue}};
+    use super::{parse_instructions, Instruction, Operation, SOLVER};
+
+    const SAMPLE: &str = "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(145));
+    }
+
+    #[test]
+    fn parses_instructions() {
+        assert_eq!("rn=1".parse::<Instruction>().unwrap(), Instruction { label: String::from("rn"), operation: Operation::Place(1) });
+        assert_eq!("cm-".parse::<Instruction>().unwrap(), Instruction { label: String::from("cm"), operation: Operation::Remove });
+    }
+
+    #[test]
+    fn rejects_invalid_instructions() {
+        assert!("rn=".parse::<Instruction>().is_err());
+        assert!("rn".parse::<Instruction>().is_err());
+        assert!("RN-".parse::<Instruction>().is_err());
+    }
+
+    #[test]
+    fn reports_column_of_invalid_instruction() {
+        let error = parse_instructions(&String::from("rn=1,cm-,qp=x")).err().unwrap();
+        assert!(matches!(error, Error::Parse { line: Some(1), column: Some(10), .. }));
+    }
+}
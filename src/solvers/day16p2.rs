@@ -1,8 +1,9 @@
-use std::{io::{BufRead, BufReader}, fs::File};
+use std::io::BufRead;
 
-use crate::helpers::{vec2d::Vec2d, direction::Directions, offset::Offset};
+use crate::helpers::{vec2d::Vec2d, direction::Directions};
 
-use super::Solver;
+use crate::error::Result;
+use super::{mark_parsed, parse_char_grid, read_lines, Answer, Solver};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum Tile {
@@ -18,15 +19,15 @@ type MirrorGrid = Vec2d<Tile>;
 type LightGrid = Vec2d<Directions>;
 
 
-fn parse_mirror_grid(input: BufReader<File>) -> MirrorGrid {
-    Vec2d::from_strings(input.lines().map(|line| line.unwrap()), |c| match c {
-        '.' => Tile::Empty,
-        '\\' => Tile::Mirror45AntiClockwise,
-        '/' => Tile::Mirror45Clockwise,
-        '-' => Tile::SplitterHorizontal,
-        '|' => Tile::SplitterVertical,
-        _ => panic!("Invalid tile")
-    }).expect("Invalid mirror grid")
+fn parse_mirror_grid(input: &mut dyn BufRead) -> Result<MirrorGrid> {
+    parse_char_grid(read_lines(input)?.into_iter(), |c| match c {
+        '.' => Ok(Tile::Empty),
+        '\\' => Ok(Tile::Mirror45AntiClockwise),
+        '/' => Ok(Tile::Mirror45Clockwise),
+        '-' => Ok(Tile::SplitterHorizontal),
+        '|' => Ok(Tile::SplitterVertical),
+        _ => Err(format!("invalid tile '{}'", c))
+    })
 }
 
 fn light_directions(grid: &MirrorGrid, beam: (usize, usize, Directions)) -> Directions {
@@ -80,12 +81,7 @@ fn light_directions(grid: &MirrorGrid, beam: (usize, usize, Directions)) -> Dire
 
 fn track_beam<'a>(grid: &'a MirrorGrid, beam: (usize, usize, Directions)) -> impl Iterator<Item = (usize, usize, Directions)> + 'a {
     let (row, col, _) = beam;
-    light_directions(grid, beam)
-        .iter()
-        .filter_map(move |d| {
-            let (new_width, new_height) = grid.offset_position(row, col, Offset::from_direction(d))?;
-            Some((new_width, new_height, d))
-        })
+    grid.directional_neighbours(row, col, light_directions(grid, beam))
 }
 
 fn compute_light_grid(grid: &MirrorGrid, initial_beam: (usize, usize, Directions)) -> LightGrid {
@@ -132,10 +128,33 @@ fn maximal_light_grid(grid: &MirrorGrid) -> (LightGrid, usize) {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-       let grid = parse_mirror_grid(input);
+       let grid = parse_mirror_grid(input)?;
+       mark_parsed();
 
        let (_, lit) = maximal_light_grid(&grid);
 
-       println!("Lit cells: {}", lit);
+        Ok(Answer::labelled("Lit cells", lit))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(51));
+    }
+}
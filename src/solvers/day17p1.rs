@@ -1,17 +1,13 @@
-use std::{io::BufRead, collections::HashMap};
+use std::io::BufRead;
 
-use crate::helpers::{vec2d::Vec2d, offset::Offset};
+use crate::helpers::{vec2d::Vec2d, offset::Offset, search};
 
-use super::Solver;
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use super::{mark_parsed, parse_char_grid, read_lines, Answer, Solver};
 use lazy_static::lazy_static;
 
-use priority_queue::PriorityQueue;
-
 type CityGrid = Vec2d<i64>;
 
-type DirectionalDistances = HashMap<((usize, usize), Offset), i64>;
-
 const MAX_LINEAR_DISTANCE: i64 = 3;
 
 lazy_static! {
@@ -48,97 +44,71 @@ fn directional_move_cost(grid: &CityGrid, from: ((usize, usize), Offset), to: ((
     cost
 }
 
-fn compute_distance_map(grid: &CityGrid, pos: (usize, usize)) -> DirectionalDistances {
-    let mut distances: DirectionalDistances = HashMap::new();
-
-    // NB: Our highest priority is the lowest distance.
-    let mut queue = PriorityQueue::new();
-    queue.push((pos, Offset::new(0, 0)), 0);
-    
-    while let Some((item, prio)) = queue.pop() {
-        let dist = -prio;
-        let (pos, offset) = item;
-
-        distances.insert((pos, offset), dist);
-
-        // println!("Processing {:?} with distance {}", item, dist);
+// A block along with the offset of the straight line that led into it,
+// since that decides which ways the crucible is allowed to turn next
+type CrucibleState = ((usize, usize), Offset);
 
-        let next_items = allowed_directions(grid, pos, offset);
-
-        for (next_pos, next_offset) in next_items {
-            
+fn next_states(grid: &CityGrid, state: &CrucibleState) -> Vec<(CrucibleState, i64)> {
+    let (pos, offset) = *state;
+    allowed_directions(grid, pos, offset)
+        .map(|(next_pos, next_offset)| {
             let final_offset = if next_offset.is_same_direction(offset) {offset + next_offset} else {next_offset};
+            let cost = directional_move_cost(grid, (pos, offset), (next_pos, final_offset));
+            ((next_pos, final_offset), cost)
+        })
+        .collect()
+}
 
-            if distances.contains_key(&(next_pos, final_offset)) {
-                continue;
-            }
-
-            let next_dist = dist + directional_move_cost(grid, (pos, offset), (next_pos, final_offset));
-
-            // println!("    Next item: {:?} {:?} with weight: {}", next_pos, final_offset, next_dist);
-
-            if let Some(existing) = queue.get(&(next_pos, final_offset)) {
-                if *existing.1 < next_dist {
-                    continue;
-                }
-            }
-
-            queue.push((next_pos, final_offset), -next_dist); // Mind the negative sign!
-        }
-    }
-    
-    distances
-
-} 
-
-fn distance_to_precomputed(distances: &DirectionalDistances, target: (usize, usize)) -> i64 {
-    let distances = ALL_OFFSETS.iter()
-        .filter_map(|offset| distances.get(&(target, *offset)))
-        .collect_vec();
-    
-    **distances.iter().min()
-        .unwrap()
+fn least_heat_loss(grid: &CityGrid, start: (usize, usize), target: (usize, usize)) -> Option<i64> {
+    search::dijkstra(
+        [(start, Offset::new(0, 0))],
+        |state| next_states(grid, state),
+        |(pos, _)| *pos == target
+    ).goal_cost()
 }
 
-fn parse_grid(input: impl BufRead) -> CityGrid {
-    Vec2d::from_strings(
-        input.lines().map(|line| line.unwrap()), 
-        |c| c.to_digit(10).expect("Invalid block number") as i64
+fn parse_grid(input: &mut dyn BufRead) -> Result<CityGrid> {
+    parse_char_grid(
+        read_lines(input)?.into_iter(),
+        |c| c.to_digit(10).map(|d| d as i64).ok_or_else(|| format!("invalid block number '{}'", c))
     )
-    .expect("Invalid city grid")
 }
 
-// fn print_grid(grid: &CityGrid) {
-//     for row in grid.iter_rows() {
-//         for block in row {
-//             print!("{}", block);
-//         }
-//         println!();
-//     }
-// }
-
 pub const SOLVER: Solver = Solver {
     solve: |input| {
 
-        let grid = parse_grid(input);
-
-        // print_grid(&grid);
+        let grid = parse_grid(input)?;
+        mark_parsed();
 
-        // let distances = compute_distance_map(&grid, (0, 0));
+        let distance = least_heat_loss(&grid, (0, 0), (grid.height-1, grid.width-1))
+            .ok_or_else(|| Error::NoSolution("the crucible cannot reach the bottom right block".to_string()))?;
 
-        // (0..grid.height).for_each(|row| {
-        //     (0..grid.width).for_each(|col| {
-        //         let distance = distance_to_precomputed(&distances, (row, col));
-        //         print!("{:3} ", distance);
-        //     });
-        //     println!();
-        // });
-
-        // println!("ALL OFFSETS: {:?}", ALL_OFFSETS.iter().collect_vec());
-        // println!("Debug hashmap: {:?}", distances);
-
-        let distance = distance_to_precomputed(&compute_distance_map(&grid, (0, 0)), (grid.height-1, grid.width-1));
-
-        println!("Distance: {}", distance);
+        Ok(Answer::labelled("Distance", distance))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(102));
+    }
+}
@@ -1,25 +1,48 @@
-use std::io::BufRead;
-
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let control_nums = input.lines().map(|line| {
-            let bytes = line.unwrap().into_bytes();
+        let lines = read_lines(input)?;
+        mark_parsed();
+
+        let control_nums = lines.into_iter().enumerate().map(|(i, line)| {
+            let bytes = line.into_bytes();
             let first = bytes.iter().find(|c| c.is_ascii_digit());
             let last = bytes.iter().rev().find(|c| c.is_ascii_digit());
             if first.is_none() {
-                println!("Weird line with no numbers");
-                return 0;
+                return Err(Error::parse_at_line(i, "line has no digits"));
             }
             
             let control_number= 
                 (*first.unwrap() as char).to_digit(10).unwrap() * 10 + (*last.unwrap() as char).to_digit(10).unwrap();
-            return control_number;
+            return Ok(control_number);
         });
 
-        let control_sum: u32 = control_nums.sum();
-        println!("Control sum: {control_sum}");
+        let control_sum = control_nums.sum::<Result<u32>>()?;
+        Ok(Answer::labelled("Control sum", control_sum))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(142));
+    }
+
+    #[test]
+    fn rejects_lines_without_digits() {
+        assert!(SOLVER.solve(&mut "abc\n12".as_bytes()).is_err());
     }
-};
\ No newline at end of file
+}
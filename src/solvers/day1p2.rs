@@ -1,5 +1,5 @@
-use std::io::BufRead;
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 struct Pattern {
     text: &'static str,
@@ -30,8 +30,10 @@ const PATTERNS: &[Pattern; 18] = &[
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let control_nums = input.lines().map(|line| {
-            let l = line.unwrap();
+        let lines = read_lines(input)?;
+        mark_parsed();
+
+        let control_nums = lines.into_iter().enumerate().map(|(i, l)| {
 
             /*
             Now, I do realise there are way more efficient ways to run this.
@@ -51,15 +53,34 @@ pub const SOLVER: Solver = Solver {
                 .max_by(|a, b| a.0.cmp(&b.0));
             
             if first.is_none() {
-                println!("Weird line with no numbers");
-                return 0;
+                return Err(Error::parse_at_line(i, "line has no digits"));
             }
 
             let control_number= first.unwrap().1.val * 10 + last.unwrap().1.val;
-            return control_number;
+            return Ok(control_number);
         });
 
-        let control_sum: u32 = control_nums.sum();
-        println!("Control sum: {control_sum}");
+        let control_sum = control_nums.sum::<Result<u32>>()?;
+        Ok(Answer::labelled("Control sum", control_sum))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(281));
     }
-};
\ No newline at end of file
+}
@@ -1,8 +1,9 @@
-use std::io::BufRead;
 use regex::Regex;
 use lazy_static::lazy_static;
 
-use super::Solver;
+use crate::error::{Error, Result};
+
+use super::{mark_parsed, parse_lines, Answer, Solver};
 
 const NUM_REDS:i32   = 12;
 const NUM_GREENS:i32 = 13;
@@ -23,41 +24,40 @@ struct Game {
     rounds: Vec<Round>
 }
 
-fn parse_round(round_str: &str) -> Round {
+fn parse_round(round_str: &str) -> Result<Round> {
     let mut round = Round {reds: 0, blues: 0, greens: 0};
 
-    round_str.split(',').for_each(|part| {
-        let part_info = ROUND_PART_REGEX.captures(part).unwrap_or_else(|| panic!("Round part must be valid: '{}'", part));
-        let count = part_info.get(1).unwrap().as_str().parse::<i32>().unwrap();
+    for part in round_str.split(',') {
+        let part_info = ROUND_PART_REGEX.captures(part).ok_or_else(|| Error::parse(format!("invalid round part: '{}'", part)))?;
+        let count = part_info.get(1).unwrap().as_str().parse::<i32>()?;
         let kind = part_info.get(2).unwrap().as_str();
         match kind {
             "red" => round.reds = count,
             "green" => round.greens = count,
             "blue" => round.blues = count,
-            _ => panic!("Non-rgb part of round found")
+            _ => return Err(Error::parse("non-rgb part of round found"))
         }
-    });
+    }
 
-    round
+    Ok(round)
 }
-fn parse_game(str: &String) -> Game {
-    let line_info = LINE_REGEX.captures(str).expect("Should be valid game line");
+fn parse_game(str: &String) -> Result<Game> {
+    let line_info = LINE_REGEX.captures(str).ok_or_else(|| Error::parse("expected a line like 'Game 1: 3 blue, 4 red'"))?;
     // TODO: Like, this is all guaranteed by \d+, but all these unwrap calls are icky.
     // See if there's a parser library that's simple and low overhead
-    let id = line_info.get(1).unwrap().as_str().parse::<i32>().unwrap();
+    let id = line_info.get(1).unwrap().as_str().parse::<i32>()?;
     
     let round_strs = line_info.get(2).unwrap().as_str().split(';');
-    return Game {
+    return Ok(Game {
         id,
-        rounds: round_strs.map(parse_round).collect()
-    }
+        rounds: round_strs.map(parse_round).collect::<Result<_>>()?
+    })
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let games = input.lines().map(|line|
-            parse_game(&line.unwrap())
-        );
+        let games = parse_lines(input, |line| parse_game(&line))?.into_iter();
+        mark_parsed();
 
         let valid_games = games.filter(|g|
             g.rounds.iter().all(|round| 
@@ -67,6 +67,24 @@ pub const SOLVER: Solver = Solver {
         );
 
         let answer: i32 = valid_games.map(|g| g.id).sum();
-        println!("{}", answer);
+        Ok(Answer::from(answer))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(8));
+    }
+}
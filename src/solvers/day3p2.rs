@@ -1,6 +1,7 @@
-use std::{io::{BufRead, BufReader}, fs::File};
+use std::io::BufRead;
 
-use super::Solver;
+use crate::error::Result;
+use super::{mark_parsed, Answer, Solver};
 
 
 #[derive(Debug)]
@@ -22,16 +23,16 @@ struct Schematic {
     symbols: Vec<Symbol>
 }
 
-fn parse_schematic(input: BufReader<File>) -> Schematic {
+fn parse_schematic(input: impl BufRead) -> Result<Schematic> {
     let mut numbers = Vec::new();
     let mut symbols = Vec::new();
 
-    let mut row = 0;
-    for line in input.lines() {
+    for (row, line) in input.lines().enumerate() {
+        let row = row as i32;
         let mut col = 0;
         let mut current_number = None;
         let mut current_number_start = None;
-        for c in line.unwrap_or_default().chars() {
+        for c in line?.chars() {
             if c.is_ascii_digit() {
                 if current_number.is_none() {
                     current_number = Some(0);
@@ -39,9 +40,9 @@ fn parse_schematic(input: BufReader<File>) -> Schematic {
                 }
                 current_number = Some(current_number.unwrap() * 10 + c.to_digit(10).unwrap());
             } else {
-                if current_number.is_some() {
+                if let Some(value) = current_number {
                     numbers.push(Number {
-                        value: current_number.unwrap(),
+                        value,
                         row,
                         start_col: current_number_start.unwrap(),
                         end_col: col-1
@@ -60,21 +61,20 @@ fn parse_schematic(input: BufReader<File>) -> Schematic {
             }
             col += 1;
         }
-        if current_number.is_some() {
+        if let Some(value) = current_number {
             numbers.push(Number {
-                value: current_number.unwrap(),
+                value,
                 row,
                 start_col: current_number_start.unwrap(),
                 end_col: col-1
             });
         }
-        row += 1;
     }
 
-    Schematic {
+    Ok(Schematic {
         numbers,
         symbols
-    }
+    })
 }
 
 fn is_adjacent(num: &Number, symbol: &Symbol) -> bool {
@@ -93,7 +93,8 @@ fn is_adjacent(num: &Number, symbol: &Symbol) -> bool {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let schematic = parse_schematic(input);
+        let schematic = parse_schematic(input)?;
+        mark_parsed();
 
         let gear_ratios = 
             schematic.symbols.iter()
@@ -103,6 +104,29 @@ pub const SOLVER: Solver = Solver {
             .map(|nums| nums.fold(1, |a, b| a * b.value));
         
         let sum_ratios: u32 = gear_ratios.sum();
-        println!("{}", sum_ratios);
+        Ok(Answer::from(sum_ratios))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(467835));
     }
-};
\ No newline at end of file
+}
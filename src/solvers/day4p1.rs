@@ -1,7 +1,8 @@
-use std::{collections::HashSet, io::BufRead};
+use std::collections::HashSet;
 use lazy_static::lazy_static;
 use regex::Regex;
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, parse_lines, Answer, Solver};
 
 lazy_static! {
     static ref CARD_REGEX: Regex = Regex::new(r"Card\s+(?P<id>\d+): (?P<actual>.*) \| (?P<guessed>.*)").unwrap();
@@ -13,19 +14,20 @@ struct Card {
     guessed: HashSet<i32>
 }
 
-fn parse_numbers(str: &str) -> HashSet<i32> {
-    str.split_whitespace().map(|s| s.parse::<i32>().unwrap()).collect()
+fn parse_numbers(str: &str) -> Result<HashSet<i32>> {
+    str.split_whitespace().map(|s| Ok(s.parse::<i32>()?)).collect()
 }
-fn parse_card(line: String) -> Card {
-    let captures = CARD_REGEX.captures(&line).unwrap();
-    let id = captures.name("id").unwrap().as_str().parse::<i32>().unwrap();
-    let actual = parse_numbers(captures.name("actual").unwrap().as_str());
-    let guessed = parse_numbers(captures.name("guessed").unwrap().as_str());
-    Card {
+fn parse_card(line: String) -> Result<Card> {
+    let captures = CARD_REGEX.captures(&line)
+        .ok_or_else(|| Error::parse("expected a card like 'Card 1: 41 48 | 83 86'"))?;
+    let id = captures["id"].parse::<i32>()?;
+    let actual = parse_numbers(&captures["actual"])?;
+    let guessed = parse_numbers(&captures["guessed"])?;
+    Ok(Card {
         _id: id,
         actual,
         guessed
-    }
+    })
 }
 
 fn correct_guesses(card: Card) -> u32 {
@@ -34,14 +36,34 @@ fn correct_guesses(card: Card) -> u32 {
 
 fn score(card: Card) -> i32 {
     let correct = correct_guesses(card);
-    return if correct == 0 {0} else {(2 as i32).pow(correct-1)}
+    return if correct == 0 {0} else {2_i32.pow(correct-1)}
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let cards = input.lines().map(|l| parse_card(l.unwrap()));
+        let cards = parse_lines(input, parse_card)?.into_iter();
+        mark_parsed();
 
         let total_score:i32 = cards.map(score).sum();
-        println!("Total score: {}", total_score);
+        Ok(Answer::labelled("Total score", total_score))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(13));
     }
-};
\ No newline at end of file
+}
@@ -1,102 +1,77 @@
-use std::io::BufRead;
 use lazy_static::lazy_static;
 use regex::Regex;
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{day5p2::{parse_maps, parse_numbers}, mark_parsed, read_lines, Answer, Solver};
 
 lazy_static! {
     static ref SEED_REGEX: Regex = Regex::new(r"seeds: (?P<seeds>.*)").unwrap();
-    static ref MAP_REGEX: Regex = Regex::new(r"(?P<id>.*) map:").unwrap();
 }
 
-struct Range {
-    source_start: u64,
-    dest_start: u64,
-    length: u64
-}
-struct RangeMap {
-    _name: String,
-    ranges: Vec<Range>
+fn parse_seeds<I: Iterator<Item = (usize, String)>>(it: &mut I) -> Result<Vec<u64>> {
+    let (idx, line_str) = it.next().ok_or_else(|| Error::parse("missing seeds line"))?;
+    let captures = SEED_REGEX.captures(&line_str)
+        .ok_or_else(|| Error::parse_at_line(idx, "expected a line like 'seeds: 79 14 55 13'"))?;
+    let seeds = parse_numbers(&captures["seeds"]).map_err(|e| e.on_line(idx))?;
+    let _skip_empty_line = it.next();
+    return Ok(seeds);
 }
 
-fn parse_numbers(line: &str) -> Vec<u64> {
-    line.split_whitespace().map(|part| part.parse::<u64>().unwrap()).collect()
-}
+pub const SOLVER: Solver = Solver {
+    solve: |input| {
+        let mut lines = read_lines(input)?.into_iter().enumerate();
+        let seeds = parse_seeds(&mut lines)?;
+        let seed_to_location = parse_maps(&mut lines)?;
+        mark_parsed();
 
-fn parse_seeds<I: Iterator<Item = std::io::Result<String>>>(it: &mut I) -> Option<Vec<u64>> {
-    let line = it.next();
-    if let Some(Ok(line_str)) = line {
-        let captures = SEED_REGEX.captures(&line_str).unwrap();
-        let seeds = parse_numbers(captures.name("seeds").unwrap().as_str());
-        let _skip_empty_line = it.next();
-        return Some(seeds);
-    }
-    return None;
-}
-    
+        let mapped_seeds = seeds.iter().map(|seed| seed_to_location.apply(*seed));
 
-fn parse_map<I: Iterator<Item = std::io::Result<String>>>(it: &mut I) -> Option<RangeMap> {
-    let description_line = it.next();
-    if let Some(Ok(description)) = description_line {
-        let captures = MAP_REGEX.captures(&description).unwrap();
-        let name = captures.name("id").unwrap().as_str().to_string();
-        let mut ranges = Vec::new();
-        for line in it {
-            let line_str = line.expect("Should end map with empty line instead of EOF");
-            if line_str == "" {
-                break;
-            }
-            let parts = parse_numbers(&line_str);
-            ranges.push(Range {
-                source_start: parts[1],
-                dest_start: parts[0],
-                length: parts[2]
-            });
-        }
-        let map = RangeMap {
-            _name: name,
-            ranges
-        };
-        return Some(map);
+        let least_location = mapped_seeds.min().ok_or_else(|| Error::NoSolution("no seeds to map".to_string()))?;
+        Ok(Answer::labelled("Least location", least_location))
     }
-    return None;
-}
+};
 
-fn parse_maps<I: Iterator<Item = std::io::Result<String>>>(it: &mut I) -> Vec<RangeMap> {
-    let mut maps = Vec::new();
-    while let Some(map) = parse_map(it) {
-        maps.push(map);
-    }
-    maps
-}
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
 
-fn apply_range_map(map: &RangeMap, value: u64) -> u64 {
-    let mut result = value;
-    for range in &map.ranges {
-        if value >= range.source_start && value < range.source_start + range.length {
-            result = range.dest_start + (value - range.source_start);
-            break;
-        }
-    }
-    result
-}
+    const SAMPLE: &str = "\
+seeds: 79 14 55 13
 
-fn apply_range_maps(maps: &Vec<RangeMap>, value: u64) -> u64 {
-    let mut result = value;
-    for map in maps {
-        result = apply_range_map(map, result);
-    }
-    result
-}
+seed-to-soil map:
+50 98 2
+52 50 48
 
-pub const SOLVER: Solver = Solver {
-    solve: |input| {
-        let mut lines = input.lines();
-        let seeds = parse_seeds(&mut lines).expect("Expected seeds");
-        let maps = parse_maps(&mut lines);
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
 
-        let mapped_seeds = seeds.iter().map(|seed| apply_range_maps(&maps, *seed));
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
 
-        let least_location = mapped_seeds.min().unwrap();
-        println!("Least location: {}", least_location);
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(35));
     }
-};
\ No newline at end of file
+}
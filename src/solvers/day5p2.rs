@@ -1,134 +1,148 @@
-use std::{io::BufRead, cmp::min};
 use lazy_static::lazy_static;
 use regex::Regex;
-use super::Solver;
+use crate::{error::{Error, Result}, helpers::interval::{Interval, IntervalSet, PiecewiseMap}};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 lazy_static! {
     static ref SEED_REGEX: Regex = Regex::new(r"seeds: (?P<seeds>.*)").unwrap();
     static ref MAP_REGEX: Regex = Regex::new(r"(?P<id>.*) map:").unwrap();
 }
 
-struct Range {
-    start: u64,
-    length: u64
-}
-
-struct RangeMapSegment {
-    source_start: u64,
-    dest_start: u64,
-    length: u64
-}
-struct RangeMap {
-    _name: String,
-    ranges: Vec<RangeMapSegment>
-}
+pub(super) type RangeMap = PiecewiseMap<u64>;
 
-fn parse_numbers(line: &str) -> Vec<u64> {
-    line.split_whitespace().map(|part| part.parse::<u64>().unwrap()).collect()
+pub(super) fn parse_numbers(line: &str) -> Result<Vec<u64>> {
+    line.split_whitespace().map(|part| Ok(part.parse::<u64>()?)).collect()
 }
 
-fn parse_seeds<I: Iterator<Item = std::io::Result<String>>>(it: &mut I) -> Option<Vec<Range>> {
-    let line = it.next();
-    if let Some(Ok(line_str)) = line {
-        let captures = SEED_REGEX.captures(&line_str).unwrap();
-        let seeds = parse_numbers(captures.name("seeds").unwrap().as_str());
-        let ranges = seeds
-            .chunks(2)
-            .map(|chunk| Range {start: chunk[0], length: chunk[1]})
-            .collect();
-        let _skip_empty_line = it.next();
-        return Some(ranges);
+fn parse_seeds<I: Iterator<Item = (usize, String)>>(it: &mut I) -> Result<IntervalSet<u64>> {
+    let (idx, line_str) = it.next().ok_or_else(|| Error::parse("missing seeds line"))?;
+    let captures = SEED_REGEX.captures(&line_str)
+        .ok_or_else(|| Error::parse_at_line(idx, "expected a line like 'seeds: 79 14 55 13'"))?;
+    let seeds = parse_numbers(&captures["seeds"]).map_err(|e| e.on_line(idx))?;
+    if seeds.len() % 2 != 0 {
+        return Err(Error::parse_at_line(idx, "seeds should come in start/length pairs"));
     }
-    return None;
+    let ranges = seeds
+        .chunks(2)
+        .map(|chunk| Interval::from_length(chunk[0], chunk[1]));
+    let _skip_empty_line = it.next();
+    return Ok(IntervalSet::from_intervals(ranges));
 }
     
 
-fn parse_map<I: Iterator<Item = std::io::Result<String>>>(it: &mut I) -> Option<RangeMap> {
+fn parse_map<I: Iterator<Item = (usize, String)>>(it: &mut I) -> Result<Option<RangeMap>> {
     let description_line = it.next();
-    if let Some(Ok(description)) = description_line {
-        let captures = MAP_REGEX.captures(&description).unwrap();
-        let name = captures.name("id").unwrap().as_str().to_string();
-        let mut ranges = Vec::new();
-        for line in it {
-            let line_str = line.expect("Should end map with empty line instead of EOF");
-            if line_str == "" {
+    if let Some((idx, description)) = description_line {
+        if !MAP_REGEX.is_match(&description) {
+            return Err(Error::parse_at_line(idx, "expected a map header like 'seed-to-soil map:'"));
+        }
+        let mut segments = Vec::new();
+        for (idx, line_str) in it.by_ref() {
+            if line_str.is_empty() {
                 break;
             }
-            let parts = parse_numbers(&line_str);
-            ranges.push(RangeMapSegment {
-                source_start: parts[1],
-                dest_start: parts[0],
-                length: parts[2]
-            });
+            let parts = parse_numbers(&line_str).map_err(|e| e.on_line(idx))?;
+            if parts.len() != 3 {
+                return Err(Error::parse_at_line(idx, format!("expected 3 numbers in a map line, found {}", parts.len())));
+            }
+            segments.push((Interval::from_length(parts[1], parts[2]), parts[0]));
         }
-        ranges.sort_by(|a, b| a.source_start.cmp(&b.source_start));
-        let map = RangeMap {
-            _name: name,
-            ranges
-        };
-        return Some(map);
+        let map = RangeMap::from_segments(segments)
+            .ok_or_else(|| Error::parse_at_line(idx, "map has overlapping source ranges"))?;
+        return Ok(Some(map));
     }
-    return None;
+    return Ok(None);
 }
 
-fn parse_maps<I: Iterator<Item = std::io::Result<String>>>(it: &mut I) -> Vec<RangeMap> {
-    let mut maps = Vec::new();
-    while let Some(map) = parse_map(it) {
-        maps.push(map);
+// Every map chained into one, straight from seed to location. Part 1 uses this too.
+pub(super) fn parse_maps<I: Iterator<Item = (usize, String)>>(it: &mut I) -> Result<RangeMap> {
+    let mut combined = RangeMap::identity();
+    while let Some(map) = parse_map(it)? {
+        combined = combined.then(&map);
     }
-    maps
+    Ok(combined)
 }
 
-fn apply_map_to_range(map: &RangeMap, range: Range) -> Vec<Range> {
-    let mut result = Vec::new();
-    let mut current_start = range.start;
-    let mut remaining = range.length;
-    for segment in &map.ranges {
-        if remaining == 0 {
-            break;
-        }
-        if current_start > segment.source_start + segment.length {
-            continue;
-        }
-        if current_start >= segment.source_start {
-            let overlap = min(remaining, segment.source_start + segment.length - current_start);
-            result.push(Range {start: segment.dest_start + (current_start - segment.source_start), length: overlap});
-            current_start += overlap;
-            remaining -= overlap;
-        } else {
-            let overlap = min(remaining, segment.source_start - current_start);
-            result.push(Range {start: current_start, length: overlap});
-            current_start += overlap;
-            remaining -= overlap;
-        }
+pub const SOLVER: Solver = Solver {
+    solve: |input| {
+        let mut lines = read_lines(input)?.into_iter().enumerate();
+        let seeds = parse_seeds(&mut lines)?;
+        let seed_to_location = parse_maps(&mut lines)?;
+        mark_parsed();
+
+        let least_location = seed_to_location.map_set(&seeds).min()
+            .ok_or_else(|| Error::NoSolution("no seeds to map".to_string()))?;
+        Ok(Answer::labelled("Least location", least_location))
     }
-    if remaining > 0 {
-        result.push(Range {start: current_start, length: remaining});
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, solvers::{solve_sample, AnswerValue}};
+    use super::{parse_map, SOLVER};
+
+    fn numbered(lines: &[&str]) -> impl Iterator<Item = (usize, String)> {
+        lines.iter().map(|line| line.to_string()).collect::<Vec<_>>().into_iter().enumerate()
     }
-    result
-}
 
-fn apply_all_maps_to_ranges(maps: &Vec<RangeMap>, ranges: Vec<Range>) -> Vec<Range> {
-    let mut result = ranges;
-    for map in maps {
-        let mut new_result = Vec::new();
-        for range in result {
-            new_result.append(&mut apply_map_to_range(map, range));
-        }
-        result = new_result;
+    const SAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(46));
     }
-    result
-}
 
-pub const SOLVER: Solver = Solver {
-    solve: |input| {
-        let mut lines = input.lines();
-        let seeds = parse_seeds(&mut lines).expect("Expected seeds");
-        let maps = parse_maps(&mut lines);
+    #[test]
+    fn parses_map_up_to_blank_line() {
+        let mut lines = numbered(&["seed-to-soil map:", "50 98 2", "52 50 48", "", "soil-to-fertilizer map:"]);
+        let map = parse_map(&mut lines).unwrap().unwrap();
 
-        let mapped_seeds = apply_all_maps_to_ranges(&maps, seeds);
+        assert_eq!([49, 50, 97, 98, 99].map(|seed| map.apply(seed)), [49, 52, 99, 50, 51]);
+        assert_eq!(lines.next().map(|(_, line)| line).as_deref(), Some("soil-to-fertilizer map:"));
+    }
 
-        let least_location = mapped_seeds.iter().min_by(|a, b| a.start.cmp(&b.start)).unwrap().start;
-        println!("Least location: {}", least_location);
+    #[test]
+    fn parse_map_stops_at_end_of_input() {
+        assert!(parse_map(&mut numbered(&[])).unwrap().is_none());
     }
-};
\ No newline at end of file
+
+    #[test]
+    fn parse_map_reports_bad_lines() {
+        let error = parse_map(&mut numbered(&["seed-to-soil map:", "50 98"])).err().unwrap();
+        assert!(matches!(error, Error::Parse { line: Some(2), .. }));
+        assert!(parse_map(&mut numbered(&["seed-to-soil map:", "0 10 5", "20 12 5"])).is_err());
+    }
+}
@@ -1,59 +1,57 @@
-use std::{io::BufRead, iter::zip};
+use std::iter::zip;
 
 use itertools::Itertools;
 
-use super::Solver;
+use crate::{error::{Error, Result}, helpers::quadratic};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
-fn parse_prefixed_number_list(input: String) -> Vec<u32> {
+fn parse_prefixed_number_list(input: String) -> Result<Vec<u32>> {
     input
         .split_whitespace()
         .dropping(1) // Don't care about the header name in this case
-        .map(|s| s.parse::<u32>().expect("List must contain valid numbers"))
+        .map(|s| Ok(s.parse::<u32>()?))
         .collect()
 }
 
-fn satisfy_monotonic_predicate(predicate: impl Fn(u32) -> bool, range_start: u32, range_end: u32) -> u32 {
-    let mut left = range_start;
-    let mut right = range_end;
-    while left < right {
-        let mid = left + (right - left) / 2;
-        if predicate(mid) {
-            right = mid;
-        } else {
-            left = mid+1;
-        }
-    }
-    return left;
-}
-
-fn get_total_distance(time: u32, wind_up: u32) -> u32 {
-    wind_up * (time - wind_up)
-}
-
-fn find_record_beaters(time: u32, record: u32) -> u32 {
-    let mid = time / 2;
-    let first_viable = satisfy_monotonic_predicate(|guess| get_total_distance(time, guess) > record, 0, mid);
-    
-    // If even we only have one middle number that maximises the score, otherwise we have two
-    // Besides potentially the midpoint, the other solutions are symmetric,
-    2 * (mid - first_viable) + 1 + (time % 2)
+// Holding the button for w leaves w * (time - w) distance, so the winning w are where
+// -w^2 + time * w - record > 0, which the quadratic helper counts exactly
+fn find_record_beaters(time: u32, record: u32) -> u64 {
+    quadratic::count_positive(-1, time as i64, -(record as i64))
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let (line1, line2) = input
-            .lines()
-            .map(|line| line.expect("Input should have 2 lines"))
+        let (line1, line2) = read_lines(input)?
+            .into_iter()
             .collect_tuple()
-            .expect("Input should have exactly 2 lines");
+            .ok_or_else(|| Error::parse("input should have exactly 2 lines"))?;
 
-        let times = parse_prefixed_number_list(line1);
-        let records = parse_prefixed_number_list(line2);
+        let times = parse_prefixed_number_list(line1).map_err(|e| e.on_line(0))?;
+        let records = parse_prefixed_number_list(line2).map_err(|e| e.on_line(1))?;
+        if times.len() != records.len() {
+            return Err(Error::parse(format!("found {} times but {} records", times.len(), records.len())));
+        }
+        mark_parsed();
 
         let record_beaters = zip(times, records).map(|(time, record)| find_record_beaters(time, record));
 
-        let total_leeway = record_beaters.reduce(|a, b| a * b).unwrap();
+        let total_leeway = record_beaters.reduce(|a, b| a * b).ok_or_else(|| Error::NoSolution("no races in input".to_string()))?;
+
+        Ok(Answer::labelled("Total leeway", total_leeway))
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
 
-        println!("Total leeway: {}", total_leeway);
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(288));
     }
-};
\ No newline at end of file
+}
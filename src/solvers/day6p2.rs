@@ -1,57 +1,55 @@
-use std::io::BufRead;
-
 use itertools::Itertools;
 
-use super::Solver;
+use crate::{error::{Error, Result}, helpers::quadratic};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
-fn parse_prefixed_number_list(input: String) -> u64 {
-    input
+fn parse_prefixed_number_list(input: String) -> Result<u64> {
+    let number = input
         .split_whitespace()
         .dropping(1) // Don't care about the header name in this case
         .join("")
-        .parse().expect("Concatenated list must be a valid number")
+        .parse()?;
+    Ok(number)
 }
 
-fn satisfy_monotonic_predicate(predicate: impl Fn(u64) -> bool, range_start: u64, range_end: u64) -> u64 {
-    let mut left = range_start;
-    let mut right = range_end;
-    while left < right {
-        let mid = left + (right - left) / 2;
-        if predicate(mid) {
-            right = mid;
-        } else {
-            left = mid+1;
-        }
+// Holding the button for w leaves w * (time - w) distance, so the winning w are where
+// -w^2 + time * w - record > 0, which the quadratic helper counts exactly
+fn find_record_beaters(time: u64, record: u64) -> Result<u64> {
+    let limit = quadratic::COEFFICIENT_LIMIT as u64;
+    if time >= limit || record >= limit {
+        return Err(Error::parse("time and record should both be below 2^62"));
     }
-    return left;
-}
-
-fn get_total_distance(time: u64, wind_up: u64) -> u64 {
-    wind_up * (time - wind_up)
-}
-
-fn find_record_beaters(time: u64, record: u64) -> u64 {
-    let mid = time / 2;
-    let first_viable = satisfy_monotonic_predicate(|guess| get_total_distance(time, guess) > record, 0, mid);
-    
-    // If even we only have one middle number that maximises the score, otherwise we have two
-    // Besides potentially the midpoint, the other solutions are symmetric,
-    2 * (mid - first_viable) + 1 + (time % 2)
+    Ok(quadratic::count_positive(-1, time as i64, -(record as i64)))
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let (line1, line2) = input
-            .lines()
-            .map(|line| line.expect("Input should have 2 lines"))
+        let (line1, line2) = read_lines(input)?
+            .into_iter()
             .collect_tuple()
-            .expect("Input should have exactly 2 lines");
+            .ok_or_else(|| Error::parse("input should have exactly 2 lines"))?;
 
-        let time = parse_prefixed_number_list(line1);
-        let record = parse_prefixed_number_list(line2);
+        let time = parse_prefixed_number_list(line1).map_err(|e| e.on_line(0))?;
+        let record = parse_prefixed_number_list(line2).map_err(|e| e.on_line(1))?;
+        mark_parsed();
 
-        let record_beaters = find_record_beaters(time, record);
+        let record_beaters = find_record_beaters(time, record)?;
 
-        println!("Total leeway: {}", record_beaters);
+        Ok(Answer::labelled("Total leeway", record_beaters))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(71503));
+    }
+}
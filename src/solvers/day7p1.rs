@@ -1,7 +1,8 @@
-use std::{io::BufRead, collections::HashMap, cmp::Ordering};
+use std::{collections::HashMap, cmp::Ordering};
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, parse_lines, Answer, Solver};
 
 lazy_static! {
     static ref CARD_VAL: HashMap<char, u32> = HashMap::from([
@@ -31,7 +32,7 @@ fn hand_group_sizes<'a>(hand: &'a String) -> impl Iterator<Item=u32> + 'a {
         let count = groups.entry(c).or_insert(0);
         *count += 1;
     }
-    groups.iter().map(|(_, v)| *v).sorted_by(|a, b| b.cmp(a))
+    groups.values().copied().sorted_by(|a, b| b.cmp(a))
 }
 
 fn lexicographic_order<It1: Iterator<Item = u32>, It2: Iterator<Item=u32>>(vec1: It1, vec2: It2) -> Ordering {
@@ -67,17 +68,18 @@ struct Entry {
     bet: u32
 }
 
-fn parse_entry(line: &String) -> Entry {
+fn parse_entry(line: &String) -> Result<Entry> {
     let mut parts = line.split_whitespace();
-    let hand = parts.next().unwrap().to_string();
-    let bet = parts.next()
-        .expect("Each line needs to have a hand component and bet component")
-        .parse::<u32>()
-        .expect("Bet component must be a valid number");
-    Entry {
-        hand,
-        bet
+    let (hand, bet) = parts.next().zip(parts.next())
+        .ok_or_else(|| Error::parse("each line needs to have a hand component and bet component"))?;
+    // Hands always start the line, so the position within the hand is the column
+    if let Some((col, c)) = hand.chars().enumerate().find(|(_, c)| !CARD_VAL.contains_key(c)) {
+        return Err(Error::parse_at_column(col, format!("invalid card '{}'", c)));
     }
+    Ok(Entry {
+        hand: hand.to_string(),
+        bet: bet.parse::<u32>()?
+    })
 }
 
 fn bet_payouts<It: Iterator<Item = Entry>>(entries: It) -> u32 {
@@ -88,10 +90,29 @@ fn bet_payouts<It: Iterator<Item = Entry>>(entries: It) -> u32 {
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let entries = input.lines().map(|line| parse_entry(&line.unwrap()));
+        let entries = parse_lines(input, |line| parse_entry(&line))?.into_iter();
+        mark_parsed();
 
         let payouts = bet_payouts(entries);
 
-        println!("Total payouts: {}", payouts);
+        Ok(Answer::labelled("Total payouts", payouts))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(6440));
+    }
+}
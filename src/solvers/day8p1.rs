@@ -1,6 +1,7 @@
-use std::{io::BufRead, collections::HashMap};
+use std::collections::{HashMap, HashSet};
 
-use super::Solver;
+use crate::error::{Error, Result};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 struct Node {
     id: String,
@@ -10,22 +11,46 @@ struct Node {
 
 type Graph = HashMap<String, Node>;
 
-fn parse_graph<Itr: Iterator<Item = std::io::Result<String>>>(lines: Itr) -> Graph {
+fn parse_node(line_str: &str) -> Result<Node> {
+    let invalid = || Error::parse("expected a node like 'AAA = (BBB, CCC)'");
+    let (id, neighbours) = line_str.split_once(" = ").ok_or_else(invalid)?;
+    let parentheses_stripped = neighbours.trim_matches(|c| c == '(' || c == ')');
+    let (left, right) = parentheses_stripped.split_once(", ").ok_or_else(invalid)?;
+    Ok(Node {
+        id: id.to_string(),
+        left: left.to_string(),
+        right: right.to_string()
+    })
+}
+
+fn parse_graph<Itr: Iterator<Item = (usize, String)>>(lines: Itr) -> Result<Graph> {
     let mut graph = HashMap::new();
-    for line in lines {
-        let line_str = line.unwrap();
-        let parts: Vec<&str> = line_str.split(" = ").collect();
-        let id = parts[0].to_string();
-        let parentheses_stripped = parts[1].trim_matches(|c| c == '(' || c == ')');
-        let neighbours: Vec<String> = parentheses_stripped.split(", ").map(|s| s.to_string()).collect();
-        let node = Node {
-            id: id.clone(),
-            left: neighbours[0].clone(),
-            right: neighbours[1].clone()
-        };
-        graph.insert(id, node);
+    let mut line_of_node = HashMap::new();
+    for (idx, line_str) in lines {
+        let node = parse_node(&line_str).map_err(|e| e.on_line(idx))?;
+        line_of_node.insert(node.id.clone(), idx);
+        graph.insert(node.id.clone(), node);
+    }
+
+    // Check every edge up front, so following the path never has to
+    for node in graph.values() {
+        for neighbour in [&node.left, &node.right] {
+            if !graph.contains_key(neighbour) {
+                return Err(Error::parse_at_line(line_of_node[&node.id], format!("unknown node {}", neighbour)));
+            }
+        }
+    }
+    return Ok(graph);
+}
+
+fn parse_instructions(line: String) -> Result<String> {
+    if line.is_empty() {
+        return Err(Error::parse_at_line(0, "instructions are empty"));
+    }
+    match line.chars().position(|c| c != 'L' && c != 'R') {
+        Some(col) => Err(Error::parse_at(0, col, "instructions can only contain L and R")),
+        None => Ok(line)
     }
-    return graph;
 }
 
 fn follow_path<'a>(graph: &'a Graph, start: &'a Node, path: &'a String) -> &'a Node {
@@ -39,32 +64,63 @@ fn follow_path<'a>(graph: &'a Graph, start: &'a Node, path: &'a String) -> &'a N
     })
 }
 
-fn steps_to_reach_end<'a>(graph: &'a Graph, start: &'a Node, end: &'a Node, path: &'a String) -> usize {
+fn steps_to_reach_end<'a>(graph: &'a Graph, start: &'a Node, end: &'a Node, path: &'a String) -> Result<usize> {
     let mut steps = 0;
     let mut current = start;
+    // Every pass through the path starts from one of these, so coming back to one means we're going round in circles
+    let mut pass_starts = HashSet::new();
     while current.id != end.id {
+        if !pass_starts.insert(&current.id) {
+            return Err(Error::NoSolution(format!("{} can't be reached from {}", end.id, start.id)));
+        }
         steps += path.len();
         current = follow_path(graph, current, path);
     }
-    return steps;
+    return Ok(steps);
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let mut lines = input.lines();
+        let mut lines = read_lines(input)?.into_iter().enumerate();
 
-        let instructions = lines.next().expect("Expected instructions").unwrap();
-        lines.next().expect("Expected empty line after instructions").unwrap();
+        let (_, instructions) = lines.next().ok_or_else(|| Error::parse("expected instructions"))?;
+        let instructions = parse_instructions(instructions)?;
+        lines.next().ok_or_else(|| Error::parse("expected empty line after instructions"))?;
 
-        let graph = parse_graph(lines);
+        let graph = parse_graph(lines)?;
+        mark_parsed();
 
         let steps = steps_to_reach_end(
             &graph, 
-            &graph.get("AAA").unwrap(), 
-            &graph.get("ZZZ").unwrap(), 
+            graph.get("AAA").ok_or_else(|| Error::parse("graph has no AAA node"))?,
+            graph.get("ZZZ").ok_or_else(|| Error::parse("graph has no ZZZ node"))?,
             &instructions.to_string()
-        );
+        )?;
 
-        println!("Steps: {}", steps);
+        Ok(Answer::labelled("Steps", steps))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(6));
+    }
+
+    #[test]
+    fn rejects_unreachable_end() {
+        let unreachable = "L\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert!(SOLVER.solve(&mut unreachable.as_bytes()).is_err());
+    }
+}
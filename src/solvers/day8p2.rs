@@ -1,8 +1,9 @@
-use std::{io::BufRead, collections::HashMap};
+use std::collections::{HashMap, HashSet};
 
-use num::integer::lcm;
+use itertools::Itertools;
 
-use super::Solver;
+use crate::{error::{Error, Result}, helpers::{cycle, numtheory::{merge_progressions, MergeError, Progression}}};
+use super::{mark_parsed, read_lines, Answer, Solver};
 
 struct Node {
     id: String,
@@ -12,33 +13,46 @@ struct Node {
 
 type Graph = HashMap<String, Node>;
 
-fn parse_graph<Itr: Iterator<Item = std::io::Result<String>>>(lines: Itr) -> Graph {
+fn parse_node(line_str: &str) -> Result<Node> {
+    let invalid = || Error::parse("expected a node like 'AAA = (BBB, CCC)'");
+    let (id, neighbours) = line_str.split_once(" = ").ok_or_else(invalid)?;
+    let parentheses_stripped = neighbours.trim_matches(|c| c == '(' || c == ')');
+    let (left, right) = parentheses_stripped.split_once(", ").ok_or_else(invalid)?;
+    Ok(Node {
+        id: id.to_string(),
+        left: left.to_string(),
+        right: right.to_string()
+    })
+}
+
+fn parse_graph<Itr: Iterator<Item = (usize, String)>>(lines: Itr) -> Result<Graph> {
     let mut graph = HashMap::new();
-    for line in lines {
-        let line_str = line.unwrap();
-        let parts: Vec<&str> = line_str.split(" = ").collect();
-        let id = parts[0].to_string();
-        let parentheses_stripped = parts[1].trim_matches(|c| c == '(' || c == ')');
-        let neighbours: Vec<String> = parentheses_stripped.split(", ").map(|s| s.to_string()).collect();
-        let node = Node {
-            id: id.clone(),
-            left: neighbours[0].clone(),
-            right: neighbours[1].clone()
-        };
-        graph.insert(id, node);
+    let mut line_of_node = HashMap::new();
+    for (idx, line_str) in lines {
+        let node = parse_node(&line_str).map_err(|e| e.on_line(idx))?;
+        line_of_node.insert(node.id.clone(), idx);
+        graph.insert(node.id.clone(), node);
     }
-    return graph;
-}
 
-fn follow_path<'a>(graph: &'a Graph, start: &'a Node, path: &'a String) -> &'a Node {
-    
-    path.chars().fold(start, |node, c| {
-        match c {
-            'L' => graph.get(&node.left).unwrap(),
-            'R' => graph.get(&node.right).unwrap(),
-            _ => panic!("Invalid path character: {}", c)
+    // Check every edge up front, so following the path never has to
+    for node in graph.values() {
+        for neighbour in [&node.left, &node.right] {
+            if !graph.contains_key(neighbour) {
+                return Err(Error::parse_at_line(line_of_node[&node.id], format!("unknown node {}", neighbour)));
+            }
         }
-    })
+    }
+    return Ok(graph);
+}
+
+fn parse_instructions(line: String) -> Result<String> {
+    if line.is_empty() {
+        return Err(Error::parse_at_line(0, "instructions are empty"));
+    }
+    match line.chars().position(|c| c != 'L' && c != 'R') {
+        Some(col) => Err(Error::parse_at(0, col, "instructions can only contain L and R")),
+        None => Ok(line)
+    }
 }
 
 fn is_start_position(node: &Node) -> bool {
@@ -48,38 +62,111 @@ fn is_end_position(node: &Node) -> bool {
     node.id.contains('Z')
 }
 
-fn steps_to_reach_end<'a>(graph: &'a Graph, start: &'a Node, path: &'a String) -> usize {
-    let mut steps = 0;
-    let mut current = start;
-    while !is_end_position(current) {
-        steps += path.len();
-        current = follow_path(graph, current, path);
+// Where a ghost is: the node it's on and how far through the instructions it has got
+type GhostState<'a> = (&'a str, usize);
+
+fn step_ghost<'a>(graph: &'a Graph, path: &[char], state: &mut GhostState<'a>) {
+    let (id, index) = *state;
+    let node = &graph[id];
+    let next = if path[index] == 'L' {&node.left} else {&node.right};
+    *state = (next.as_str(), (index + 1) % path.len());
+}
+
+// Every step count at which a ghost is on an end node. A ghost always ends up going round a cycle,
+// so ends before the cycle happen just once and those on it come back every time round.
+fn end_progressions(graph: &Graph, start: &Node, path: &[char]) -> Vec<Progression> {
+    let initial: GhostState = (start.id.as_str(), 0);
+    let cycle = cycle::find_cycle_hashed(initial, |state| step_ghost(graph, path, state));
+
+    let mut state = initial;
+    let mut progressions = Vec::new();
+    for step in 0..cycle.start + cycle.length {
+        if is_end_position(&graph[state.0]) {
+            progressions.push(if step < cycle.start {
+                Progression::once(step as i64)
+            } else {
+                Progression::new(step as i64, cycle.length as i64)
+            });
+        }
+        step_ghost(graph, path, &mut state);
     }
-    return steps;
+    progressions
 }
 
-fn steps_to_reach_end_simultaneously<'a>(graph: &'a Graph, path: &'a String) -> usize {
-    let start = graph.values().filter(|node| is_start_position(node));
-    
-    let individual_steps = start.map(|start_node| steps_to_reach_end(graph, start_node, path));
+fn steps_to_reach_end_simultaneously(graph: &Graph, path: &String) -> Result<i64> {
+    let path = path.chars().collect::<Vec<_>>();
+    let mut starts = graph.values().filter(|node| is_start_position(node)).peekable();
+    if starts.peek().is_none() {
+        return Err(Error::NoSolution("no start positions in graph".to_string()));
+    }
 
-    return individual_steps.reduce(lcm).expect("Expected at least one start position");
+    // Every way the ghosts so far can all be on an end node at once
+    let mut together = vec![Progression::new(0, 1)];
+    for start in starts {
+        let ends = end_progressions(graph, start, &path);
+        let mut merged = HashSet::new();
+        for (a, b) in together.iter().cartesian_product(&ends) {
+            match merge_progressions(*a, *b) {
+                Ok(progression) => {merged.insert(progression);},
+                Err(MergeError::Disjoint) => (),
+                Err(MergeError::Overflow) => return Err(Error::NoSolution("the ghosts take too many steps to count in an i64".to_string()))
+            }
+        }
+        // Different pairs of ends can line up the same way, and there's no need to carry duplicates forward
+        together = merged.into_iter().collect();
+    }
+
+    together.iter()
+        .map(|progression| progression.first)
+        .min()
+        .ok_or_else(|| Error::NoSolution("the ghosts never all reach an end at the same time".to_string()))
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let mut lines = input.lines();
+        let mut lines = read_lines(input)?.into_iter().enumerate();
 
-        let instructions = lines.next().expect("Expected instructions").unwrap();
-        lines.next().expect("Expected empty line after instructions").unwrap();
+        let (_, instructions) = lines.next().ok_or_else(|| Error::parse("expected instructions"))?;
+        let instructions = parse_instructions(instructions)?;
+        lines.next().ok_or_else(|| Error::parse("expected empty line after instructions"))?;
 
-        let graph = parse_graph(lines);
+        let graph = parse_graph(lines)?;
+        mark_parsed();
 
         let steps = steps_to_reach_end_simultaneously(
             &graph, 
             &instructions.to_string()
-        );
+        )?;
 
-        println!("Steps: {}", steps);
+        Ok(Answer::labelled("Steps", steps))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::{parse_instructions, SOLVER};
+
+    const SAMPLE: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(6));
+    }
+
+    #[test]
+    fn rejects_bad_instructions() {
+        assert!(parse_instructions(String::new()).is_err());
+        assert!(parse_instructions("LRX".to_string()).is_err());
+    }
+}
@@ -1,32 +1,48 @@
-use std::io::BufRead;
+use num::ToPrimitive;
 
-use itertools::Itertools;
-use num::Zero;
+use crate::{error::{Error, Result}, helpers::polynomial::NewtonPolynomial};
+use super::{mark_parsed, parse_lines, Answer, Solver};
 
-use super::Solver;
-
-fn parse_sequence(input: String) -> Vec<i64> {
-    input.split_whitespace().map(|s| s.parse::<i64>().expect("Expected numbers only")).collect()
-}
-
-fn predict_next_number(sequence: &Vec<i64>) -> i64 {
-    let mut accumulated = 0;
-    
-    let mut next_sequence = sequence.clone();
-    while !next_sequence.iter().all(|n| n.is_zero()) {
-        accumulated += next_sequence.last().expect("Expected at least one number");
-        next_sequence = next_sequence.iter().tuple_windows().map(|(a, b)| b - a).collect();
+fn parse_sequence(input: String) -> Result<Vec<i64>> {
+    let sequence = input.split_whitespace().map(|s| s.parse::<i64>()).collect::<std::result::Result<Vec<_>, _>>()?;
+    if sequence.is_empty() {
+        return Err(Error::parse("expected at least one number"));
     }
+    Ok(sequence)
+}
 
-    accumulated
+// Samples of a polynomial always come out as integers at integer positions
+fn predict_next_number(sequence: &Vec<i64>) -> Result<i64> {
+    NewtonPolynomial::fit(sequence).evaluate(sequence.len() as i64)
+        .to_integer()
+        .to_i64()
+        .ok_or_else(|| Error::NoSolution("prediction is too large for 64 bits".to_string()))
 }
 
 pub const SOLVER: Solver = Solver {
     solve: |input| {
-        let sequences = input.lines().map(|line| parse_sequence(line.unwrap()));
-        let next_numbers = sequences.map(|seq| predict_next_number(&seq));
+        let sequences = parse_lines(input, parse_sequence)?.into_iter();
+        mark_parsed();
+
+        let next_numbers = sequences.map(|seq| predict_next_number(&seq)).collect::<Result<Vec<_>>>()?;
 
-        let total = next_numbers.sum::<i64>();
-        println!("Total: {}", total);
+        let total = next_numbers.iter().sum::<i64>();
+        Ok(Answer::labelled("Total", total))
     }
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
+
+    const SAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(114));
+    }
+}
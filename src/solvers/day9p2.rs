@@ -1,34 +1,48 @@
-use std::io::BufRead;
+use num::ToPrimitive;
 
-use itertools::Itertools;
-use num::Zero;
+use crate::{error::{Error, Result}, helpers::polynomial::NewtonPolynomial};
+use super::{mark_parsed, parse_lines, Answer, Solver};
 
-use super::Solver;
+fn parse_sequence(input: String) -> Result<Vec<i64>> {
+    let sequence = input.split_whitespace().map(|s| s.parse::<i64>()).collect::<std::result::Result<Vec<_>, _>>()?;
+    if sequence.is_empty() {
+        return Err(Error::parse("expected at least one number"));
+    }
+    Ok(sequence)
+}
 
-fn parse_sequence(input: String) -> Vec<i64> {
-    input.split_whitespace().map(|s| s.parse::<i64>().expect("Expected numbers only")).collect()
+// Samples of a polynomial always come out as integers at integer positions
+fn predict_previous_number(sequence: &Vec<i64>) -> Result<i64> {
+    NewtonPolynomial::fit(sequence).evaluate(-1)
+        .to_integer()
+        .to_i64()
+        .ok_or_else(|| Error::NoSolution("prediction is too large for 64 bits".to_string()))
 }
 
-fn predict_next_number(sequence: &Vec<i64>) -> i64 {
-    let mut accumulated = *sequence.first().expect("Expected at least one number");
-    let mut next_sequence = sequence.clone();
-    let mut sign = 1;
+pub const SOLVER: Solver = Solver {
+    solve: |input| {
+        let sequences = parse_lines(input, parse_sequence)?.into_iter();
+        mark_parsed();
+
+        let previous_numbers = sequences.map(|seq| predict_previous_number(&seq)).collect::<Result<Vec<_>>>()?;
 
-    while !next_sequence.iter().all(|n| n.is_zero()) {
-        next_sequence = next_sequence.iter().tuple_windows().map(|(a, b)| b - a).collect();
-        accumulated -= sign * next_sequence.first().expect("Expected at least one number");
-        sign *= -1;
+        let total = previous_numbers.iter().sum::<i64>();
+        Ok(Answer::labelled("Total", total))
     }
+};
 
-    accumulated
-}
+#[cfg(test)]
+mod tests {
+    use crate::solvers::{solve_sample, AnswerValue};
+    use super::SOLVER;
 
-pub const SOLVER: Solver = Solver {
-    solve: |input| {
-        let sequences = input.lines().map(|line| parse_sequence(line.unwrap()));
-        let next_numbers = sequences.map(|seq| predict_next_number(&seq));
+    const SAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
 
-        let total = next_numbers.sum::<i64>();
-        println!("Total: {}", total);
+    #[test]
+    fn solves_sample() {
+        assert_eq!(solve_sample(&SOLVER, SAMPLE), AnswerValue::from(2));
     }
-};
\ No newline at end of file
+}